@@ -1,5 +1,5 @@
 use actix::prelude::*;
-use hitbox::dev::{Backend, BackendError, Delete, DeleteStatus, Get, Lock, LockStatus, Set};
+use hitbox::dev::{Backend, BackendError, Bytes, Delete, DeleteStatus, Get, Lock, LockStatus, Set};
 use hitbox_actix::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -41,7 +41,7 @@ impl Backend for DummyBackend {
 }
 
 impl Handler<Get> for DummyBackend {
-    type Result = ResponseFuture<Result<Option<Vec<u8>>, BackendError>>;
+    type Result = ResponseFuture<Result<Option<Bytes>, BackendError>>;
 
     fn handle(&mut self, _msg: Get, _: &mut Self::Context) -> Self::Result {
         log::warn!("Dummy backend GET");
@@ -79,6 +79,15 @@ impl Handler<Lock> for DummyBackend {
     }
 }
 
+impl Handler<hitbox::dev::Ping> for DummyBackend {
+    type Result = Result<(), BackendError>;
+
+    fn handle(&mut self, _msg: hitbox::dev::Ping, _: &mut Self::Context) -> Self::Result {
+        log::warn!("Dummy backend Ping");
+        Ok(())
+    }
+}
+
 #[actix::main]
 async fn main() -> Result<(), CacheError> {
     env_logger::builder()
@@ -10,11 +10,13 @@ impl Actor for UpstreamActor {
     type Context = Context<Self>;
 }
 
+#[allow(dead_code)]
 enum CacheableResult<T, U> {
     Cacheable(T),
     NoneCacheable(U),
 }
 
+#[allow(dead_code)]
 trait CacheableResponse<T, E> {
     fn cache(&self) -> CacheableResult<&T, &E>;
 }
@@ -1,5 +1,5 @@
 use actix::prelude::*;
-use hitbox::dev::{Backend, BackendError, Delete, DeleteStatus, Get, Lock, LockStatus, Set};
+use hitbox::dev::{Backend, BackendError, Bytes, Delete, DeleteStatus, Get, Lock, LockStatus, Set};
 use hitbox_actix::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -50,7 +50,7 @@ impl Backend for DummySyncBackend {
 }
 
 impl Handler<Get> for DummySyncBackend {
-    type Result = Result<Option<Vec<u8>>, BackendError>;
+    type Result = Result<Option<Bytes>, BackendError>;
 
     fn handle(&mut self, _msg: Get, _: &mut Self::Context) -> Self::Result {
         log::warn!("Dummy sync backend GET");
@@ -85,6 +85,15 @@ impl Handler<Lock> for DummySyncBackend {
     }
 }
 
+impl Handler<hitbox::dev::Ping> for DummySyncBackend {
+    type Result = Result<(), BackendError>;
+
+    fn handle(&mut self, _msg: hitbox::dev::Ping, _: &mut Self::Context) -> Self::Result {
+        log::warn!("Dummy sync backend Ping");
+        Ok(())
+    }
+}
+
 #[actix::main]
 async fn main() -> Result<(), CacheError> {
     env_logger::builder()
@@ -1,16 +1,15 @@
 //! Cache actor and Builder.
 use crate::builder::CacheBuilder;
+use crate::writer::{WriteQueue, WriteWorker};
 use actix::dev::ToEnvelope;
 use actix::prelude::*;
 use hitbox::dev::{Delete, Get, Lock, Set};
-#[cfg(feature = "metrics")]
-use hitbox::metrics::{
-    CACHE_HIT_COUNTER, CACHE_MISS_COUNTER, CACHE_STALE_COUNTER, CACHE_UPSTREAM_HANDLING_HISTOGRAM,
-};
+use hitbox::runtime::{CircuitBreaker, RetryPolicy};
 use hitbox::settings::CacheSettings;
 use hitbox::CacheError;
 use hitbox_backend::Backend;
 use hitbox_redis::RedisBackend;
+use std::time::Duration;
 use tracing::{debug, info};
 
 /// Actix actor implements cache logic.
@@ -37,6 +36,16 @@ where
 {
     pub(crate) settings: CacheSettings,
     pub(crate) backend: Addr<B>,
+    pub(crate) backend_timeout: Option<Duration>,
+    pub(crate) circuit_breaker: Option<CircuitBreaker>,
+    pub(crate) upstream_timeout: Option<Duration>,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) background_writes: bool,
+    pub(crate) write_queue: Option<WriteQueue>,
+    pub(crate) write_worker: Option<WriteWorker>,
+    pub(crate) namespace: Option<String>,
+    pub(crate) namespace_version: Option<u32>,
+    pub(crate) ttl_multiplier: f64,
 }
 
 impl<B> CacheActor<B>
@@ -61,6 +70,24 @@ where
     }
 }
 
+impl<B> CacheActor<B>
+where
+    B: Backend,
+{
+    /// Returns the prefix of all cache keys composed from the namespace and the global version.
+    pub fn key_prefix(&self) -> String {
+        let mut prefix = String::new();
+        if let Some(namespace) = &self.namespace {
+            prefix.push_str(namespace);
+            prefix.push_str("::");
+        }
+        if let Some(version) = self.namespace_version {
+            prefix.push_str(&format!("v{}::", version));
+        }
+        prefix
+    }
+}
+
 impl<B> Actor for CacheActor<B>
 where
     B: Backend,
@@ -70,5 +97,8 @@ where
     fn started(&mut self, _: &mut Self::Context) {
         info!("Cache actor started");
         debug!("Cache enabled: {:?}", self.settings);
+        if let Some(worker) = self.write_worker.take() {
+            actix::spawn(worker);
+        }
     }
 }
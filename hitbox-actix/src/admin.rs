@@ -0,0 +1,239 @@
+//! Admin HTTP service for inspecting and purging the cache.
+//!
+//! Available with `admin` feature. The service is an [actix_web::Scope] with routes:
+//! * `GET /entries?prefix=Upstream::Message::&limit=100&cursor=...` - list a page of keys
+//!   with the prefix in the namespace of the cache actor, see [ListEntries]. The response
+//!   contains `keys` and the `cursor` of the next page, which is `null` after the last page.
+//! * `DELETE /entries?prefix=Upstream::Message::` - delete values with the prefix,
+//!   the prefix can't be empty, see [DeleteEntries].
+//! * `GET /entry?key=...` - show the cached value with its age, TTL and stale status,
+//!   see [InspectEntry].
+//! * `DELETE /entry?key=...` - delete the cached value, see [DeleteEntry].
+//! * `GET /stats` - backend statistics, size per message type and write-behind queue counters.
+//!
+//! Keys of `/entry` routes are full backend keys, as returned by `/entries`. Keys outside
+//! the namespace of the cache actor are reported as missing, and inspected values keep
+//! their expiration and eviction order.
+//! The service doesn't authenticate requests, so it should be mounted behind
+//! an authentication middleware or on an internal port.
+//!
+//! # Example
+//! ```ignore
+//! HttpServer::new(move || {
+//!     App::new().service(admin_scope("/cache", cache.clone()))
+//! })
+//! ```
+use actix::dev::ToEnvelope;
+use actix::prelude::*;
+use actix_web::{web, HttpResponse, Scope};
+use hitbox::dev::{Backend, BackendStats, Delete, GetMetadata, KeySizes, Peek, Scan, Stats};
+use hitbox::CacheError;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    CacheActor, CacheEntry, DeleteEntries, DeleteEntry, GetBackendStats, GetMessageStats,
+    GetWriteBehindStats, InspectEntry, ListEntries, MessageStats, WriteBehindStats,
+};
+
+/// Creates admin service of the cache actor mounted at the path.
+pub fn admin_scope<B>(path: &str, cache: Addr<CacheActor<B>>) -> Scope
+where
+    B: Backend
+        + Handler<Scan>
+        + Handler<KeySizes>
+        + Handler<Stats>
+        + Handler<GetMetadata>
+        + Handler<Peek>,
+    <B as Actor>::Context: ToEnvelope<B, Delete>
+        + ToEnvelope<B, Scan>
+        + ToEnvelope<B, KeySizes>
+        + ToEnvelope<B, Stats>
+        + ToEnvelope<B, GetMetadata>
+        + ToEnvelope<B, Peek>,
+{
+    web::scope(path)
+        .app_data(web::Data::new(cache))
+        .service(
+            web::resource("/entries")
+                .route(web::get().to(list_entries::<B>))
+                .route(web::delete().to(delete_entries::<B>)),
+        )
+        .service(
+            web::resource("/entry")
+                .route(web::get().to(inspect_entry::<B>))
+                .route(web::delete().to(delete_entry::<B>)),
+        )
+        .service(web::resource("/stats").route(web::get().to(stats::<B>)))
+}
+
+type CacheAddr<B> = web::Data<Addr<CacheActor<B>>>;
+
+/// Number of keys listed by `/entries` route if the limit is missing.
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct PrefixQuery {
+    #[serde(default)]
+    prefix: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntriesQuery {
+    #[serde(default)]
+    prefix: String,
+    cursor: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyQuery {
+    key: String,
+}
+
+#[derive(Debug, Serialize)]
+struct EntryResponse {
+    key: String,
+    bytes: usize,
+    created: Option<String>,
+    expired: String,
+    age_seconds: Option<i64>,
+    ttl_seconds: Option<u64>,
+    stale: bool,
+    schema_version: u32,
+    data: serde_json::Value,
+}
+
+impl From<CacheEntry> for EntryResponse {
+    fn from(entry: CacheEntry) -> Self {
+        let stale = entry.is_stale();
+        let record = entry.record;
+        Self {
+            key: entry.key,
+            bytes: entry.bytes,
+            created: record.created.map(|created| created.to_rfc3339()),
+            expired: record.expired.to_rfc3339(),
+            age_seconds: record.age().map(|age| age.num_seconds()),
+            ttl_seconds: entry.metadata.ttl.map(|ttl| ttl.as_secs()),
+            stale,
+            schema_version: record.schema_version,
+            data: record.data,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct StatsResponse {
+    backend: serde_json::Value,
+    messages: Vec<MessageStats>,
+    write_behind: Option<WriteBehindStats>,
+}
+
+fn error_response(error: impl std::fmt::Display) -> HttpResponse {
+    HttpResponse::InternalServerError().json(json!({ "error": error.to_string() }))
+}
+
+/// Converts the cache actor response into HTTP response.
+fn respond<T>(
+    result: Result<Result<T, CacheError>, MailboxError>,
+    response: impl FnOnce(T) -> HttpResponse,
+) -> HttpResponse {
+    match result {
+        Ok(Ok(value)) => response(value),
+        Ok(Err(error)) => error_response(error),
+        Err(error) => error_response(error),
+    }
+}
+
+async fn list_entries<B>(cache: CacheAddr<B>, query: web::Query<EntriesQuery>) -> HttpResponse
+where
+    B: Backend + Handler<Scan>,
+    <B as Actor>::Context: ToEnvelope<B, Scan>,
+{
+    let EntriesQuery {
+        prefix,
+        cursor,
+        limit,
+    } = query.into_inner();
+    let list = ListEntries {
+        prefix,
+        cursor,
+        limit: Some(limit.unwrap_or(DEFAULT_PAGE_SIZE)),
+    };
+    let result = cache.send(list).await;
+    respond(result, |page| {
+        HttpResponse::Ok().json(json!({ "keys": page.keys, "cursor": page.cursor }))
+    })
+}
+
+async fn delete_entries<B>(cache: CacheAddr<B>, query: web::Query<PrefixQuery>) -> HttpResponse
+where
+    B: Backend + Handler<Scan>,
+    <B as Actor>::Context: ToEnvelope<B, Scan> + ToEnvelope<B, Delete>,
+{
+    let prefix = query.into_inner().prefix;
+    if prefix.is_empty() {
+        return HttpResponse::BadRequest().json(json!({ "error": "Prefix is required" }));
+    }
+    let result = cache.send(DeleteEntries { prefix }).await;
+    respond(result, |deleted| {
+        HttpResponse::Ok().json(json!({ "deleted": deleted }))
+    })
+}
+
+async fn inspect_entry<B>(cache: CacheAddr<B>, query: web::Query<KeyQuery>) -> HttpResponse
+where
+    B: Backend + Handler<GetMetadata> + Handler<Peek>,
+    <B as Actor>::Context: ToEnvelope<B, GetMetadata> + ToEnvelope<B, Peek>,
+{
+    let key = query.into_inner().key;
+    let result = cache.send(InspectEntry { key }).await;
+    respond(result, |entry| match entry {
+        Some(entry) => HttpResponse::Ok().json(EntryResponse::from(entry)),
+        None => HttpResponse::NotFound().finish(),
+    })
+}
+
+async fn delete_entry<B>(cache: CacheAddr<B>, query: web::Query<KeyQuery>) -> HttpResponse
+where
+    B: Backend,
+    <B as Actor>::Context: ToEnvelope<B, Delete>,
+{
+    let key = query.into_inner().key;
+    let result = cache.send(DeleteEntry { key }).await;
+    respond(result, |deleted| match deleted {
+        true => HttpResponse::NoContent().finish(),
+        false => HttpResponse::NotFound().finish(),
+    })
+}
+
+async fn stats<B>(cache: CacheAddr<B>) -> HttpResponse
+where
+    B: Backend + Handler<Scan> + Handler<KeySizes> + Handler<Stats>,
+    <B as Actor>::Context: ToEnvelope<B, Scan> + ToEnvelope<B, KeySizes> + ToEnvelope<B, Stats>,
+{
+    let backend = match cache.send(GetBackendStats).await {
+        Ok(Ok(stats)) => stats,
+        Ok(Err(error)) => return error_response(error),
+        Err(error) => return error_response(error),
+    };
+    let messages = match cache.send(GetMessageStats).await {
+        Ok(Ok(stats)) => stats,
+        Ok(Err(error)) => return error_response(error),
+        Err(error) => return error_response(error),
+    };
+    let write_behind = match cache.send(GetWriteBehindStats).await {
+        Ok(stats) => stats,
+        Err(error) => return error_response(error),
+    };
+    let BackendStats {
+        entries,
+        bytes,
+        evictions,
+    } = backend;
+    HttpResponse::Ok().json(StatsResponse {
+        backend: json!({ "entries": entries, "bytes": bytes, "evictions": evictions }),
+        messages,
+        write_behind,
+    })
+}
@@ -1,9 +1,16 @@
 //! CacheActor builder patter implementation.
+use crate::runtime::is_valid_ttl_multiplier;
+use crate::writer::{WriteBehind, WriteOverflow, WriteQueue};
 use crate::CacheActor;
+use actix::dev::ToEnvelope;
 use actix::{Actor, Addr};
+use hitbox::dev::Set;
+use hitbox::runtime::{CircuitBreaker, RetryPolicy};
 use hitbox::settings::{CacheSettings, Status};
 use hitbox_backend::Backend;
 use std::marker::PhantomData;
+use std::time::Duration;
+use tracing::warn;
 
 /// Cache actor configurator.
 ///
@@ -29,6 +36,15 @@ where
     B: Backend + Actor,
 {
     settings: CacheSettings,
+    backend_timeout: Option<Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+    upstream_timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    background_writes: bool,
+    write_behind: Option<WriteBehind>,
+    namespace: Option<String>,
+    namespace_version: Option<u32>,
+    ttl_multiplier: f64,
     _p: PhantomData<B>,
 }
 
@@ -43,7 +59,16 @@ where
                 stale: Status::Enabled,
                 lock: Status::Disabled,
             },
-            _p: PhantomData::default(),
+            backend_timeout: None,
+            circuit_breaker: None,
+            upstream_timeout: None,
+            retry_policy: RetryPolicy::default(),
+            background_writes: false,
+            write_behind: None,
+            namespace: None,
+            namespace_version: None,
+            ttl_multiplier: 1.0,
+            _p: PhantomData,
         }
     }
 }
@@ -101,6 +126,170 @@ where
         self
     }
 
+    /// Limit the duration of each cache backend operation.
+    ///
+    /// If the backend does not respond in time, the operation is treated as a backend
+    /// connection error: cache read falls back to the upstream and cache write is skipped.
+    pub fn with_backend_timeout(mut self, timeout: Duration) -> Self {
+        self.backend_timeout = Some(timeout);
+        self
+    }
+
+    /// Wait for cache backend responses without time limit. (Default value).
+    pub fn without_backend_timeout(mut self) -> Self {
+        self.backend_timeout = None;
+        self
+    }
+
+    /// Enable circuit breaker for cache backend operations.
+    ///
+    /// After `failure_threshold` consecutive backend connection errors (or timeouts)
+    /// [CacheActor] stops interacting with cache backend and passes all messages
+    /// directly to upstream actors. After `reset_timeout` one message is allowed to
+    /// check the backend again; if it succeeds, cache interaction is restored.
+    pub fn with_circuit_breaker(mut self, failure_threshold: u32, reset_timeout: Duration) -> Self {
+        self.circuit_breaker = Some(CircuitBreaker::new(failure_threshold, reset_timeout));
+        self
+    }
+
+    /// Disable circuit breaker for cache backend operations. (Default value).
+    pub fn without_circuit_breaker(mut self) -> Self {
+        self.circuit_breaker = None;
+        self
+    }
+
+    /// Limit the duration of each upstream polling attempt.
+    ///
+    /// If the upstream actor does not respond in time, the attempt is treated as an
+    /// upstream error. With enabled stale mechanics the stale cached value is returned.
+    pub fn with_upstream_timeout(mut self, timeout: Duration) -> Self {
+        self.upstream_timeout = Some(timeout);
+        self
+    }
+
+    /// Wait for upstream responses without time limit. (Default value).
+    pub fn without_upstream_timeout(mut self) -> Self {
+        self.upstream_timeout = None;
+        self
+    }
+
+    /// Retry failed upstream polling up to `max_retries` times.
+    ///
+    /// Delay before the first retry is `backoff`, each next delay is doubled.
+    /// Retries do nothing unless the message is marked with [QueryCache::retryable],
+    /// which requires the message to implement `Clone`:
+    /// ```ignore
+    /// let cache = CacheActor::builder()
+    ///     .with_upstream_retries(3, Duration::from_millis(100))
+    ///     .finish(backend)
+    ///     .start();
+    /// let pong = cache.send(Ping { id: 42 }.into_cache(&upstream).retryable()).await??;
+    /// ```
+    ///
+    /// [QueryCache::retryable]: crate::QueryCache::retryable
+    pub fn with_upstream_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.retry_policy = RetryPolicy::new(max_retries, backoff);
+        self
+    }
+
+    /// Disable upstream polling retries. (Default value).
+    pub fn without_upstream_retries(mut self) -> Self {
+        self.retry_policy = RetryPolicy::default();
+        self
+    }
+
+    /// Send cache updates to the backend without waiting for the response.
+    ///
+    /// The upstream value is returned as soon as it's serialized, the backend write
+    /// runs in the background. A request which comes right after the response
+    /// can miss the value which is still being written.
+    pub fn with_background_writes(mut self) -> Self {
+        self.background_writes = true;
+        self
+    }
+
+    /// Wait for the backend response before returning the upstream value. (Default value).
+    pub fn without_background_writes(mut self) -> Self {
+        self.background_writes = false;
+        self
+    }
+
+    /// Send cache updates to the backend through a bounded write-behind queue.
+    ///
+    /// The upstream value is returned as soon as it's serialized and put into the queue,
+    /// a single background worker writes queued values to the backend one by one.
+    /// When the queue holds `capacity` values, new updates are dropped or wait for
+    /// a free slot depending on `overflow`. Queue counters are available with
+    /// [GetWriteBehindStats] message. Takes precedence over [with_background_writes].
+    ///
+    /// [GetWriteBehindStats]: crate::GetWriteBehindStats
+    /// [with_background_writes]: CacheBuilder::with_background_writes
+    pub fn with_write_behind(mut self, capacity: usize, overflow: WriteOverflow) -> Self {
+        self.write_behind = Some(WriteBehind { capacity, overflow });
+        self
+    }
+
+    /// Disable write-behind queue of cache updates. (Default value).
+    pub fn without_write_behind(mut self) -> Self {
+        self.write_behind = None;
+        self
+    }
+
+    /// Prepend the namespace to every cache key, like `svc:prod::Upstream::Message::v0::id=42`.
+    ///
+    /// Allows several environments or services to share the same cache backend.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Use cache keys without namespace. (Default value).
+    pub fn without_namespace(mut self) -> Self {
+        self.namespace = None;
+        self
+    }
+
+    /// Prepend the global version to every cache key after the namespace,
+    /// like `svc:prod::v3::Upstream::Message::v0::id=42`.
+    ///
+    /// Changing the version invalidates all cached values at once. Instances sharing
+    /// the backend should be configured with the same version, so it's usually taken
+    /// from the deployment configuration.
+    pub fn with_namespace_version(mut self, version: u32) -> Self {
+        self.namespace_version = Some(version);
+        self
+    }
+
+    /// Use cache keys without global version. (Default value).
+    pub fn without_namespace_version(mut self) -> Self {
+        self.namespace_version = None;
+        self
+    }
+
+    /// Multiply TTL and stale TTL of all messages by `multiplier`.
+    ///
+    /// Allows to shorten or extend caching of all messages at once, for example `0.5`
+    /// halves every TTL. The multiplier can also be changed at runtime with
+    /// [SetTtlMultiplier] message.
+    ///
+    /// The multiplier should be a finite positive number, otherwise it's ignored with a warning.
+    ///
+    /// [SetTtlMultiplier]: crate::SetTtlMultiplier
+    pub fn with_ttl_multiplier(mut self, multiplier: f64) -> Self {
+        if is_valid_ttl_multiplier(multiplier) {
+            self.ttl_multiplier = multiplier;
+        } else {
+            warn!("Invalid cache TTL multiplier {} ignored", multiplier);
+        }
+        self
+    }
+
+    /// Use TTL values of messages as is. (Default value).
+    pub fn without_ttl_multiplier(mut self) -> Self {
+        self.ttl_multiplier = 1.0;
+        self
+    }
+
     /// Instantiate new [Cache] instance with current configuration and passed backend.
     ///
     /// Backend is an [Addr] of actix [Actor] which implements [Backend] trait:
@@ -111,10 +300,35 @@ where
     /// [Actor]: https://docs.rs/actix/latest/actix/prelude/trait.Actor.html
     /// [Messages]: https://docs.rs/actix/latest/actix/prelude/trait.Message.html
     /// [Handler]: https://docs.rs/actix/latest/actix/prelude/trait.Handler.html
-    pub fn finish(self, backend: Addr<B>) -> CacheActor<B> {
+    pub fn finish(self, backend: Addr<B>) -> CacheActor<B>
+    where
+        <B as Actor>::Context: ToEnvelope<B, Set>,
+    {
+        let (write_queue, write_worker) = match self.write_behind {
+            Some(config) => {
+                let (queue, worker) = WriteQueue::new(
+                    config,
+                    backend.clone(),
+                    self.backend_timeout,
+                    self.circuit_breaker.clone(),
+                );
+                (Some(queue), Some(worker))
+            }
+            None => (None, None),
+        };
         CacheActor {
             settings: self.settings,
             backend,
+            backend_timeout: self.backend_timeout,
+            circuit_breaker: self.circuit_breaker,
+            upstream_timeout: self.upstream_timeout,
+            retry_policy: self.retry_policy,
+            background_writes: self.background_writes,
+            write_queue,
+            write_worker,
+            namespace: self.namespace,
+            namespace_version: self.namespace_version,
+            ttl_multiplier: self.ttl_multiplier,
         }
     }
 }
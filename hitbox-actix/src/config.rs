@@ -0,0 +1,216 @@
+//! Cache configuration loaded from TOML, YAML files or environment variables.
+//!
+//! Available with `config` feature.
+use std::path::Path;
+use std::time::Duration;
+
+use config_rs::{Config, Environment, File, FileFormat, Source};
+use hitbox_backend::Backend;
+use serde::Deserialize;
+
+use crate::writer::WriteOverflow;
+use crate::CacheBuilder;
+#[cfg(feature = "redis")]
+use crate::{Cache, CacheError};
+#[cfg(feature = "redis")]
+use actix::Actor;
+#[cfg(feature = "redis")]
+use hitbox_redis::RedisConfig;
+
+pub use config_rs::ConfigError;
+
+/// Cache actor and backend configuration.
+///
+/// Missing fields have the same values as [CacheBuilder] defaults.
+/// Durations are written in human readable form, like `50ms` or `1m 30s`.
+/// TTLs are defined by [Cacheable](hitbox::Cacheable) implementations of messages,
+/// `ttl_multiplier` scales them for all messages.
+///
+/// # Example
+/// ```toml
+/// stale = false
+/// namespace = "billing:prod"
+/// backend_timeout = "50ms"
+///
+/// [circuit_breaker]
+/// failure_threshold = 5
+/// reset_timeout = "10s"
+///
+/// [redis]
+/// url = "redis://cache.local/1"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// Enable interaction with cache backend.
+    pub enabled: bool,
+    /// Enable cache stale mechanics.
+    pub stale: bool,
+    /// Namespace prepended to every cache key.
+    pub namespace: Option<String>,
+    /// Global version prepended to every cache key after the namespace.
+    pub namespace_version: Option<u32>,
+    /// Multiplier of TTL and stale TTL of all messages.
+    pub ttl_multiplier: f64,
+    /// Limit of each cache backend operation duration.
+    #[serde(with = "humantime_serde")]
+    pub backend_timeout: Option<Duration>,
+    /// Limit of each upstream polling attempt duration.
+    #[serde(with = "humantime_serde")]
+    pub upstream_timeout: Option<Duration>,
+    /// Circuit breaker for cache backend operations.
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Retries of failed upstream polling.
+    pub upstream_retries: Option<UpstreamRetriesConfig>,
+    /// Send cache updates without waiting for the backend response.
+    pub background_writes: bool,
+    /// Write-behind queue of cache updates.
+    pub write_behind: Option<WriteBehindConfig>,
+    /// Redis backend configuration.
+    #[cfg(feature = "redis")]
+    pub redis: RedisConfig,
+}
+
+/// Circuit breaker configuration, see [CacheBuilder::with_circuit_breaker].
+#[derive(Debug, Clone, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// Number of consecutive backend errors which opens the circuit.
+    pub failure_threshold: u32,
+    /// Delay before the backend is checked again.
+    #[serde(with = "humantime_serde")]
+    pub reset_timeout: Duration,
+}
+
+/// Upstream retries configuration, see [CacheBuilder::with_upstream_retries].
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpstreamRetriesConfig {
+    /// Maximum number of retries.
+    pub max_retries: u32,
+    /// Delay before the first retry, each next delay is doubled.
+    #[serde(with = "humantime_serde")]
+    pub backoff: Duration,
+}
+
+/// Write-behind queue configuration, see [CacheBuilder::with_write_behind].
+#[derive(Debug, Clone, Deserialize)]
+pub struct WriteBehindConfig {
+    /// Maximum number of cache updates waiting for the backend write.
+    pub capacity: usize,
+    /// Behavior of the queue when it's full: `drop` (default) or `wait`.
+    #[serde(default = "default_overflow")]
+    pub overflow: WriteOverflow,
+}
+
+fn default_overflow() -> WriteOverflow {
+    WriteOverflow::Drop
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stale: true,
+            namespace: None,
+            namespace_version: None,
+            ttl_multiplier: 1.0,
+            backend_timeout: None,
+            upstream_timeout: None,
+            circuit_breaker: None,
+            upstream_retries: None,
+            background_writes: false,
+            write_behind: None,
+            #[cfg(feature = "redis")]
+            redis: RedisConfig::default(),
+        }
+    }
+}
+
+impl CacheConfig {
+    fn from_source<T>(source: T) -> Result<Self, ConfigError>
+    where
+        T: Source + Send + Sync + 'static,
+    {
+        let mut config = Config::new();
+        config.merge(source)?;
+        config.try_into()
+    }
+
+    /// Loads configuration from the file, the format is detected by the file extension.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_source(File::from(path.as_ref()))
+    }
+
+    /// Loads configuration from the TOML string.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        Self::from_source(File::from_str(source, FileFormat::Toml))
+    }
+
+    /// Loads configuration from the YAML string.
+    pub fn from_yaml(source: &str) -> Result<Self, ConfigError> {
+        Self::from_source(File::from_str(source, FileFormat::Yaml))
+    }
+
+    /// Loads configuration from environment variables with the prefix.
+    ///
+    /// Nested fields are separated by double underscore, so with `HITBOX` prefix
+    /// `HITBOX_BACKEND_TIMEOUT=50ms` sets `backend_timeout`
+    /// and `HITBOX_REDIS__URL=redis://cache.local/` sets `redis.url`.
+    pub fn from_env(prefix: &str) -> Result<Self, ConfigError> {
+        Self::from_source(Environment::with_prefix(prefix).separator("__"))
+    }
+
+    /// Creates [CacheBuilder] with current configuration.
+    pub fn builder<B>(&self) -> CacheBuilder<B>
+    where
+        B: Backend,
+    {
+        let mut builder = CacheBuilder::default();
+        builder = if self.enabled {
+            builder.enable()
+        } else {
+            builder.disable()
+        };
+        builder = if self.stale {
+            builder.with_stale()
+        } else {
+            builder.without_stale()
+        };
+        if let Some(namespace) = &self.namespace {
+            builder = builder.with_namespace(namespace.as_str());
+        }
+        if let Some(version) = self.namespace_version {
+            builder = builder.with_namespace_version(version);
+        }
+        builder = builder.with_ttl_multiplier(self.ttl_multiplier);
+        if let Some(timeout) = self.backend_timeout {
+            builder = builder.with_backend_timeout(timeout);
+        }
+        if let Some(timeout) = self.upstream_timeout {
+            builder = builder.with_upstream_timeout(timeout);
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            builder =
+                builder.with_circuit_breaker(breaker.failure_threshold, breaker.reset_timeout);
+        }
+        if let Some(retries) = &self.upstream_retries {
+            builder = builder.with_upstream_retries(retries.max_retries, retries.backoff);
+        }
+        if self.background_writes {
+            builder = builder.with_background_writes();
+        }
+        if let Some(write_behind) = &self.write_behind {
+            builder = builder.with_write_behind(write_behind.capacity, write_behind.overflow);
+        }
+        builder
+    }
+
+    /// Starts Redis backend and creates [Cache] actor with current configuration.
+    #[cfg(feature = "redis")]
+    pub async fn build(&self) -> Result<Cache, CacheError> {
+        let backend = async { self.redis.builder()?.build().await }
+            .await
+            .map_err(|err| CacheError::BackendError(err.into()))?
+            .start();
+        Ok(self.builder().finish(backend))
+    }
+}
@@ -1,18 +1,72 @@
-//! Actix Handler<QueryCache> implementation.
+//! Actix Handler<QueryCache>, Handler<QueryCacheOutcome>, Handler<PeekCache>,
+//! Handler<GetWriteBehindStats>, Handler<FlushWriteBehind>, runtime settings
+//! and cache introspection handlers implementation.
 
-use crate::{ActixAdapter, CacheActor, QueryCache};
+use crate::{
+    messages::message_type,
+    runtime::{is_valid_ttl_multiplier, send_to_backend},
+    writer::{WriteBehindStats, WriteQueue},
+    ActixAdapter, CacheActor, CacheActorSettings, CacheEntry, DeleteEntries, DeleteEntry,
+    FlushWriteBehind, GetBackendStats, GetMessageStats, GetSettings, GetWriteBehindStats,
+    InspectEntry, ListEntries, MessageStats, PeekCache, QueryCache, QueryCacheOutcome,
+    SetTtlMultiplier, UpdateSettings,
+};
 use actix::{
     dev::{MessageResponse, ResponseFuture, ToEnvelope},
     prelude::*,
 };
+use hitbox::runtime::RuntimeAdapter;
+use hitbox::settings::Status;
 use hitbox::states::initial::Initial;
 use hitbox::{
-    dev::{Backend, Delete, Get, Lock, Set},
-    CacheError, Cacheable, CacheableResponse,
+    dev::{
+        escape_pattern, Backend, BackendStats, Delete, DeleteStatus, Get, GetMetadata, KeySizes,
+        Lock, Peek, Scan, ScanPage, Set, Stats,
+    },
+    CacheError, CacheOutcome, CacheState, Cacheable, CacheableResponse, CachedRecord,
 };
 use serde::{de::DeserializeOwned, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
 
-impl<'a, A, M, B> Handler<QueryCache<A, M>> for CacheActor<B>
+impl<B> CacheActor<B>
+where
+    B: Actor + Backend,
+{
+    /// Creates runtime adapter for message with current cache actor settings.
+    fn adapter<A, M>(&self, msg: QueryCache<A, M>) -> Result<ActixAdapter<A, M, B>, CacheError>
+    where
+        A: Actor + Handler<M>,
+        M: Message + Cacheable + Send,
+        M::Result: MessageResponse<A, M> + Send,
+    {
+        let adapter = ActixAdapter::new(msg, self.backend.clone())?; // @TODO: remove clone
+        Ok(adapter
+            .with_backend_timeout(self.backend_timeout)
+            .with_circuit_breaker(self.circuit_breaker.clone())
+            .with_upstream_timeout(self.upstream_timeout)
+            .with_retry_policy(self.retry_policy.clone())
+            .with_background_writes(self.background_writes)
+            .with_ttl_multiplier(self.ttl_multiplier)
+            .with_write_queue(self.write_queue.clone())
+            .with_key_prefix(&self.key_prefix()))
+    }
+}
+
+impl<B> CacheActor<B>
+where
+    B: Actor + Backend,
+{
+    /// Returns current runtime settings.
+    fn runtime_settings(&self) -> CacheActorSettings {
+        CacheActorSettings {
+            settings: self.settings.clone(),
+            ttl_multiplier: self.ttl_multiplier,
+        }
+    }
+}
+
+impl<A, M, B> Handler<QueryCache<A, M>> for CacheActor<B>
 where
     B: Actor + Backend,
     <B as Actor>::Context:
@@ -26,11 +80,330 @@ where
     type Result = ResponseFuture<Result<<M as Message>::Result, CacheError>>;
 
     fn handle(&mut self, msg: QueryCache<A, M>, _: &mut Self::Context) -> Self::Result {
-        let adapter_result = ActixAdapter::new(msg, self.backend.clone()); // @TODO: remove clone
-        let settings = self.settings.clone();
+        let settings = self.settings.apply(&msg.settings_override());
+        let adapter_result = self.adapter(msg);
         Box::pin(async move {
             let initial_state = Initial::new(settings, adapter_result?);
             initial_state.transitions().await
         })
     }
 }
+
+impl<A, M, B> Handler<QueryCacheOutcome<A, M>> for CacheActor<B>
+where
+    B: Actor + Backend,
+    <B as Actor>::Context:
+        ToEnvelope<B, Get> + ToEnvelope<B, Set> + ToEnvelope<B, Lock> + ToEnvelope<B, Delete>,
+    A: Actor + Handler<M> + Send,
+    M: Message + Cacheable + Send + 'static,
+    M::Result: MessageResponse<A, M> + CacheableResponse + std::fmt::Debug + Send,
+    <<M as actix::Message>::Result as CacheableResponse>::Cached: Serialize + DeserializeOwned,
+    <A as Actor>::Context: ToEnvelope<A, M>,
+{
+    type Result = ResponseFuture<Result<CacheOutcome<<M as Message>::Result>, CacheError>>;
+
+    fn handle(&mut self, msg: QueryCacheOutcome<A, M>, _: &mut Self::Context) -> Self::Result {
+        let settings = self.settings.apply(&msg.query.settings_override());
+        let adapter_result = self.adapter(msg.query);
+        Box::pin(async move {
+            let initial_state = Initial::new(settings, adapter_result?);
+            initial_state.transitions_with_outcome().await
+        })
+    }
+}
+
+impl<A, M, B> Handler<PeekCache<A, M>> for CacheActor<B>
+where
+    B: Actor + Backend,
+    <B as Actor>::Context:
+        ToEnvelope<B, Get> + ToEnvelope<B, Set> + ToEnvelope<B, Lock> + ToEnvelope<B, Delete>,
+    A: Actor + Handler<M> + Send,
+    M: Message + Cacheable + Send + 'static,
+    M::Result: MessageResponse<A, M> + CacheableResponse + Send,
+    <<M as actix::Message>::Result as CacheableResponse>::Cached: Serialize + DeserializeOwned,
+    <A as Actor>::Context: ToEnvelope<A, M>,
+{
+    type Result = ResponseFuture<Result<CacheState<<M as Message>::Result>, CacheError>>;
+
+    fn handle(&mut self, msg: PeekCache<A, M>, _: &mut Self::Context) -> Self::Result {
+        let settings = self.settings.apply(&msg.query.settings_override());
+        let cache_enabled = matches!(settings.cache, Status::Enabled);
+        let adapter_result = self.adapter(msg.query);
+        Box::pin(async move {
+            let adapter = adapter_result?;
+            if !cache_enabled {
+                return Ok(CacheState::Miss);
+            }
+            adapter.poll_cache().await
+        })
+    }
+}
+
+impl<B> Handler<GetWriteBehindStats> for CacheActor<B>
+where
+    B: Actor + Backend,
+{
+    type Result = Option<WriteBehindStats>;
+
+    fn handle(&mut self, _: GetWriteBehindStats, _: &mut Self::Context) -> Self::Result {
+        self.write_queue.as_ref().map(WriteQueue::stats)
+    }
+}
+
+impl<B> Handler<FlushWriteBehind> for CacheActor<B>
+where
+    B: Actor + Backend,
+{
+    type Result = ResponseFuture<()>;
+
+    fn handle(&mut self, _: FlushWriteBehind, _: &mut Self::Context) -> Self::Result {
+        let write_queue = self.write_queue.clone();
+        Box::pin(async move {
+            if let Some(write_queue) = write_queue {
+                write_queue.flush().await;
+            }
+        })
+    }
+}
+
+impl<B> Handler<GetSettings> for CacheActor<B>
+where
+    B: Actor + Backend,
+{
+    type Result = CacheActorSettings;
+
+    fn handle(&mut self, _: GetSettings, _: &mut Self::Context) -> Self::Result {
+        self.runtime_settings()
+    }
+}
+
+impl<B> Handler<UpdateSettings> for CacheActor<B>
+where
+    B: Actor + Backend,
+{
+    type Result = CacheActorSettings;
+
+    fn handle(&mut self, msg: UpdateSettings, _: &mut Self::Context) -> Self::Result {
+        self.settings = self.settings.apply(&msg.0);
+        info!("Cache settings updated: {:?}", self.settings);
+        self.runtime_settings()
+    }
+}
+
+impl<B> Handler<SetTtlMultiplier> for CacheActor<B>
+where
+    B: Actor + Backend,
+{
+    type Result = CacheActorSettings;
+
+    fn handle(&mut self, msg: SetTtlMultiplier, _: &mut Self::Context) -> Self::Result {
+        let SetTtlMultiplier(multiplier) = msg;
+        if is_valid_ttl_multiplier(multiplier) {
+            info!("Cache TTL multiplier changed to {}", multiplier);
+            self.ttl_multiplier = multiplier;
+        } else {
+            warn!("Invalid cache TTL multiplier {} ignored", multiplier);
+        }
+        self.runtime_settings()
+    }
+}
+
+/// Number of keys requested from the backend at once while scanning the whole namespace.
+const SCAN_PAGE_SIZE: usize = 1000;
+
+/// Introspection requests aren't limited by the backend timeout and don't affect
+/// the circuit breaker, because scanning large backends takes time.
+impl<B> Handler<ListEntries> for CacheActor<B>
+where
+    B: Actor + Backend + Handler<Scan>,
+    <B as Actor>::Context: ToEnvelope<B, Scan>,
+{
+    type Result = ResponseFuture<Result<ScanPage, CacheError>>;
+
+    fn handle(&mut self, msg: ListEntries, _: &mut Self::Context) -> Self::Result {
+        let prefix = format!("{}{}", self.key_prefix(), msg.prefix);
+        let scan = Scan {
+            pattern: format!("{}*", escape_pattern(&prefix)),
+            cursor: msg.cursor,
+            limit: msg.limit,
+        };
+        let backend = self.backend.clone();
+        Box::pin(async move {
+            let mut page = send_to_backend(backend, scan, None, None).await?;
+            page.keys.sort_unstable();
+            Ok(page)
+        })
+    }
+}
+
+impl<B> Handler<GetBackendStats> for CacheActor<B>
+where
+    B: Actor + Backend + Handler<Stats>,
+    <B as Actor>::Context: ToEnvelope<B, Stats>,
+{
+    type Result = ResponseFuture<Result<BackendStats, CacheError>>;
+
+    fn handle(&mut self, _: GetBackendStats, _: &mut Self::Context) -> Self::Result {
+        let backend = self.backend.clone();
+        let pattern = format!("{}*", escape_pattern(&self.key_prefix()));
+        Box::pin(async move {
+            let stats = Stats {
+                pattern: Some(pattern),
+            };
+            let stats = send_to_backend(backend, stats, None, None).await?;
+            Ok(stats)
+        })
+    }
+}
+
+impl<B> Handler<GetMessageStats> for CacheActor<B>
+where
+    B: Actor + Backend + Handler<Scan> + Handler<KeySizes>,
+    <B as Actor>::Context: ToEnvelope<B, Scan> + ToEnvelope<B, KeySizes>,
+{
+    type Result = ResponseFuture<Result<Vec<MessageStats>, CacheError>>;
+
+    fn handle(&mut self, _: GetMessageStats, _: &mut Self::Context) -> Self::Result {
+        let key_prefix = self.key_prefix();
+        let backend = self.backend.clone();
+        Box::pin(async move {
+            let mut scan = Scan {
+                pattern: format!("{}*", escape_pattern(&key_prefix)),
+                cursor: None,
+                limit: Some(SCAN_PAGE_SIZE),
+            };
+            let mut messages = BTreeMap::<String, (u64, u64)>::new();
+            loop {
+                let page = send_to_backend(backend.clone(), scan.clone(), None, None).await?;
+                let key_sizes = KeySizes { keys: page.keys };
+                let sizes = send_to_backend(backend.clone(), key_sizes.clone(), None, None).await?;
+                // Records expired after the scan have no size.
+                for (key, bytes) in key_sizes.keys.iter().zip(sizes) {
+                    if let (Some(key), Some(bytes)) = (key.strip_prefix(key_prefix.as_str()), bytes)
+                    {
+                        let stats = messages.entry(message_type(key).to_owned()).or_default();
+                        stats.0 += 1;
+                        stats.1 += bytes;
+                    }
+                }
+                match page.cursor {
+                    Some(cursor) => scan.cursor = Some(cursor),
+                    None => break,
+                }
+            }
+            Ok(messages
+                .into_iter()
+                .map(|(message, (entries, bytes))| MessageStats {
+                    message,
+                    entries,
+                    bytes,
+                })
+                .collect())
+        })
+    }
+}
+
+impl<B> Handler<InspectEntry> for CacheActor<B>
+where
+    B: Actor + Backend + Handler<GetMetadata> + Handler<Peek>,
+    <B as Actor>::Context: ToEnvelope<B, GetMetadata> + ToEnvelope<B, Peek>,
+{
+    type Result = ResponseFuture<Result<Option<CacheEntry>, CacheError>>;
+
+    fn handle(&mut self, msg: InspectEntry, _: &mut Self::Context) -> Self::Result {
+        let key = msg.key;
+        let in_namespace = key.starts_with(&self.key_prefix());
+        let backend = self.backend.clone();
+        Box::pin(async move {
+            if !in_namespace {
+                warn!(
+                    "Cache entry outside the namespace can't be inspected: {}",
+                    key
+                );
+                return Ok(None);
+            }
+            let get_metadata = GetMetadata { key: key.clone() };
+            let metadata = match send_to_backend(backend.clone(), get_metadata, None, None).await? {
+                Some(metadata) => metadata,
+                None => return Ok(None),
+            };
+            let peek = Peek { key: key.clone() };
+            let value = match send_to_backend(backend, peek, None, None).await? {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+            let record = CachedRecord::from_bytes(&key, &value)?;
+            Ok(Some(CacheEntry {
+                key,
+                bytes: value.len(),
+                record,
+                metadata,
+            }))
+        })
+    }
+}
+
+impl<B> Handler<DeleteEntry> for CacheActor<B>
+where
+    B: Actor + Backend,
+    <B as Actor>::Context: ToEnvelope<B, Delete>,
+{
+    type Result = ResponseFuture<Result<bool, CacheError>>;
+
+    fn handle(&mut self, msg: DeleteEntry, _: &mut Self::Context) -> Self::Result {
+        let in_namespace = msg.key.starts_with(&self.key_prefix());
+        let backend = self.backend.clone();
+        Box::pin(async move {
+            if !in_namespace {
+                warn!(
+                    "Cache entry outside the namespace can't be deleted: {}",
+                    msg.key
+                );
+                return Ok(false);
+            }
+            info!("Cache entry deleted: {}", msg.key);
+            let delete = Delete { key: msg.key };
+            let status = send_to_backend(backend, delete, None, None).await?;
+            Ok(matches!(status, DeleteStatus::Deleted(_)))
+        })
+    }
+}
+
+impl<B> Handler<DeleteEntries> for CacheActor<B>
+where
+    B: Actor + Backend + Handler<Scan>,
+    <B as Actor>::Context: ToEnvelope<B, Scan> + ToEnvelope<B, Delete>,
+{
+    type Result = ResponseFuture<Result<u64, CacheError>>;
+
+    fn handle(&mut self, msg: DeleteEntries, _: &mut Self::Context) -> Self::Result {
+        let prefix = format!("{}{}", self.key_prefix(), msg.prefix);
+        let pattern = format!("{}*", escape_pattern(&prefix));
+        let backend = self.backend.clone();
+        Box::pin(async move {
+            let mut scan = Scan {
+                pattern,
+                cursor: None,
+                limit: Some(SCAN_PAGE_SIZE),
+            };
+            let mut deleted = 0;
+            loop {
+                let page = send_to_backend(backend.clone(), scan.clone(), None, None).await?;
+                for key in page.keys {
+                    let delete = Delete { key };
+                    if let DeleteStatus::Deleted(count) =
+                        send_to_backend(backend.clone(), delete, None, None).await?
+                    {
+                        deleted += u64::from(count);
+                    }
+                }
+                match page.cursor {
+                    Some(cursor) => scan.cursor = Some(cursor),
+                    None => break,
+                }
+            }
+            info!("Cache entries with prefix {} deleted: {}", prefix, deleted);
+            Ok(deleted)
+        })
+    }
+}
@@ -17,11 +17,14 @@
 //!
 //! ## Backend implementations:
 //! - [x] [Redis](https://github.com/hit-box/hitbox/tree/master/hitbox-backend)
-//! - [ ] In-memory backend
+//! - [x] [In-memory](https://github.com/hit-box/hitbox/tree/master/hitbox-memory)
 //!
 //! ## Feature flags
 //! * derive - Support for [Cacheable] trait derive macros.
 //! * redis - Support for default redis backend.
+//! * redis-tls - Support for TLS connections of redis backend.
+//! * admin - Admin HTTP service for [actix-web] to inspect and purge the cache.
+//! * config - Loading of [CacheConfig] from TOML, YAML files or environment variables.
 //!
 //! ## Restrictions
 //! Default cache key implementation based on serde_qs crate
@@ -126,18 +129,34 @@
 //! [dogpile effect]: https://www.sobstel.org/blog/preventing-dogpile-effect/
 //! [Message]: actix::Message
 //! [Actix]: https://github.com/actix/actix/
+//! [actix-web]: https://github.com/actix/actix-web/
 
 pub mod actor;
+#[cfg(feature = "admin")]
+pub mod admin;
 pub mod builder;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod handlers;
 pub mod messages;
 pub mod runtime;
+pub mod writer;
 
 pub use actor::CacheActor;
+#[cfg(feature = "admin")]
+pub use admin::admin_scope;
 pub use builder::CacheBuilder;
-pub use hitbox::{CacheError, Cacheable};
-pub use messages::{IntoCache, QueryCache};
+#[cfg(feature = "config")]
+pub use config::CacheConfig;
+pub use hitbox::{CacheError, CacheOutcome, CacheState, CacheStatus, Cacheable, SettingsOverride};
+pub use messages::{
+    CacheActorSettings, CacheEntry, CacheableUpstream, DeleteEntries, DeleteEntry,
+    FlushWriteBehind, GetBackendStats, GetMessageStats, GetSettings, GetWriteBehindStats,
+    InspectEntry, IntoCache, ListEntries, MessageStats, PeekCache, QueryCache, QueryCacheOutcome,
+    SetTtlMultiplier, UpdateSettings,
+};
 pub use runtime::ActixAdapter;
+pub use writer::{WriteBehindStats, WriteOverflow};
 
 #[cfg(feature = "redis")]
 pub use hitbox_redis::RedisBackend;
@@ -151,6 +170,13 @@ pub type Cache = CacheActor<RedisBackend>;
 pub mod prelude {
     #[cfg(feature = "redis")]
     pub use crate::{Cache, RedisBackend};
-    pub use crate::{CacheActor, CacheBuilder, CacheError, Cacheable, IntoCache, QueryCache};
-    pub use hitbox::hitbox_serializer;
+    pub use crate::{
+        CacheActor, CacheActorSettings, CacheBuilder, CacheEntry, CacheError, CacheOutcome,
+        CacheState, CacheStatus, Cacheable, CacheableUpstream, DeleteEntries, DeleteEntry,
+        FlushWriteBehind, GetBackendStats, GetMessageStats, GetSettings, GetWriteBehindStats,
+        InspectEntry, IntoCache, ListEntries, MessageStats, PeekCache, QueryCache,
+        QueryCacheOutcome, SetTtlMultiplier, SettingsOverride, UpdateSettings, WriteBehindStats,
+        WriteOverflow,
+    };
+    pub use hitbox::{hitbox_serializer, HashEncoder, JsonEncoder, KeyEncoder, KeyHasher};
 }
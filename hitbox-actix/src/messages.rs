@@ -1,6 +1,9 @@
 //! QueryCache message declaration and converting.
+use crate::writer::WriteBehindStats;
 use actix::{dev::MessageResponse, prelude::*};
-use hitbox::{CacheError, Cacheable};
+use hitbox::dev::{BackendStats, RecordMetadata, ScanPage};
+use hitbox::settings::CacheSettings;
+use hitbox::{CacheError, CacheOutcome, CacheState, Cacheable, CachedRecord, SettingsOverride};
 
 /// Trait describes coversion from any [actix::Message] into QueryCache message.
 pub trait IntoCache: Cacheable {
@@ -40,12 +43,56 @@ pub trait IntoCache: Cacheable {
         QueryCache {
             upstream: upstream.clone(),
             message: self,
+            clone_message: None,
+            upstream_name: None,
+            settings: SettingsOverride::default(),
         }
     }
+
+    /// Helper method to convert Message into [QueryCache] message
+    /// with the upstream name declared by [CacheableUpstream] trait.
+    fn into_named_cache<A>(self, upstream: &Addr<A>) -> QueryCache<A, Self>
+    where
+        A: Actor + CacheableUpstream,
+        Self: Message + Send + Sized,
+        Self::Result: MessageResponse<A, Self> + Send + 'static,
+    {
+        self.into_cache(upstream)
+            .with_upstream_name(A::upstream_name())
+    }
 }
 
 impl<M: Message + Cacheable> IntoCache for M {}
 
+/// Trait declares an explicit upstream name used in cache keys.
+///
+/// By default the upstream name is derived from the actor type name, which isn't guaranteed
+/// to be stable between compiler versions and is the same for actors with the same name
+/// from different modules. Messages converted by [IntoCache::into_named_cache] use
+/// the declared name instead, so a toolchain upgrade doesn't invalidate cached values.
+///
+/// # Examples
+/// ```
+/// use actix::prelude::*;
+/// use hitbox_actix::prelude::*;
+///
+/// struct UserStorage;
+///
+/// impl Actor for UserStorage {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl CacheableUpstream for UserStorage {
+///     fn upstream_name() -> &'static str {
+///         "users"
+///     }
+/// }
+/// ```
+pub trait CacheableUpstream {
+    /// Returns the upstream name used in cache keys.
+    fn upstream_name() -> &'static str;
+}
+
 /// Intermediate actix message which handled by Cache actor.
 ///
 /// This message a product of upstream message and upstream actor address.
@@ -60,6 +107,9 @@ where
 {
     pub(crate) upstream: Addr<A>,
     pub(crate) message: M,
+    pub(crate) clone_message: Option<fn(&M) -> M>,
+    pub(crate) upstream_name: Option<&'static str>,
+    pub(crate) settings: SettingsOverride,
 }
 
 impl<A, M> QueryCache<A, M>
@@ -68,12 +118,41 @@ where
     M::Result: MessageResponse<A, M> + Send,
     A: Actor,
 {
-    /// Returns upstream actor type name or <Unknown>.
-    pub(crate) fn upstream_name(&self) -> &'static str {
-        std::any::type_name::<A>()
-            .rsplit("::")
-            .next()
-            .unwrap_or("<Unknown>")
+    /// Returns declared upstream name or upstream actor type name without module paths.
+    pub(crate) fn upstream_name(&self) -> String {
+        match self.upstream_name {
+            Some(name) => name.to_owned(),
+            None => short_type_name(std::any::type_name::<A>()),
+        }
+    }
+
+    /// Sets explicit upstream name used in cache key instead of the actor type name.
+    pub fn with_upstream_name(mut self, name: &'static str) -> Self {
+        self.upstream_name = Some(name);
+        self
+    }
+
+    /// Overrides cache actor settings for this query.
+    ///
+    /// Takes precedence over the settings of the actor and [Cacheable::cache_settings].
+    pub fn with_settings(mut self, settings: SettingsOverride) -> Self {
+        self.settings = self.settings.merge(settings);
+        self
+    }
+
+    /// Bypasses the cache for this query, the message is sent directly to the upstream actor.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let fresh = cache.send(Ping { id: 42 }.into_cache(&upstream).no_cache()).await??;
+    /// ```
+    pub fn no_cache(self) -> Self {
+        self.with_settings(SettingsOverride::default().disable())
+    }
+
+    /// Returns overrides of the message type combined with overrides of this query.
+    pub(crate) fn settings_override(&self) -> SettingsOverride {
+        self.message.cache_settings().merge(self.settings.clone())
     }
 
     /// Returns final cache key.
@@ -89,7 +168,44 @@ where
     }
 }
 
-impl<'a, A, M> Message for QueryCache<A, M>
+/// Removes module paths from the type name, including type parameters:
+/// `app::Storage<alloc::string::String>` becomes `Storage<String>`.
+fn short_type_name(name: &str) -> String {
+    let mut short = String::with_capacity(name.len());
+    let mut segment = String::new();
+    for symbol in name.chars() {
+        if symbol.is_alphanumeric() || symbol == '_' || symbol == ':' {
+            segment.push(symbol);
+        } else {
+            short.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            short.push(symbol);
+        }
+    }
+    short.push_str(segment.rsplit("::").next().unwrap_or_default());
+    short
+}
+
+impl<A, M> QueryCache<A, M>
+where
+    M: Message + Cacheable + Send + Clone,
+    M::Result: MessageResponse<A, M> + Send,
+    A: Actor,
+{
+    /// Allows [CacheActor] to resend this message to upstream actor.
+    ///
+    /// Upstream retries configured by [CacheBuilder::with_upstream_retries] apply only
+    /// to retryable messages, because each attempt needs its own copy of the message.
+    ///
+    /// [CacheActor]: crate::CacheActor
+    /// [CacheBuilder::with_upstream_retries]: crate::CacheBuilder::with_upstream_retries
+    pub fn retryable(mut self) -> Self {
+        self.clone_message = Some(M::clone);
+        self
+    }
+}
+
+impl<A, M> Message for QueryCache<A, M>
 where
     A: Actor,
     M: Message + Cacheable + Send,
@@ -97,3 +213,385 @@ where
 {
     type Result = Result<<M as Message>::Result, CacheError>;
 }
+
+impl<A, M> QueryCache<A, M>
+where
+    M: Message + Cacheable + Send,
+    M::Result: MessageResponse<A, M> + Send,
+    A: Actor,
+{
+    /// Converts QueryCache into [PeekCache] message.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let state = cache.send(Ping { id: 42 }.into_cache(&upstream).peek()).await??;
+    /// if let CacheState::Stale(value) = state {
+    ///     println!("Stale since {}", value.expired());
+    /// }
+    /// ```
+    pub fn peek(self) -> PeekCache<A, M> {
+        PeekCache { query: self }
+    }
+
+    /// Converts QueryCache into [QueryCacheOutcome] message.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// let outcome = cache.send(Ping { id: 42 }.into_cache(&upstream).with_outcome()).await??;
+    /// if let Some(age) = outcome.age() {
+    ///     println!("{:?}: cached {} seconds ago", outcome.status, age.num_seconds());
+    /// }
+    /// ```
+    pub fn with_outcome(self) -> QueryCacheOutcome<A, M> {
+        QueryCacheOutcome { query: self }
+    }
+}
+
+/// Actix message which works like [QueryCache] but returns the value with cache metadata.
+///
+/// [CacheActor](crate::CacheActor) responds with [CacheOutcome] which holds the value,
+/// the [CacheStatus](hitbox::CacheStatus) describing how it was obtained (hit, miss, stale, etc.)
+/// and timestamps of the cached value.
+pub struct QueryCacheOutcome<A, M>
+where
+    M: Message + Cacheable + Send,
+    M::Result: MessageResponse<A, M> + Send,
+    A: Actor,
+{
+    pub(crate) query: QueryCache<A, M>,
+}
+
+impl<A, M> QueryCacheOutcome<A, M>
+where
+    M: Message + Cacheable + Send,
+    M::Result: MessageResponse<A, M> + Send,
+    A: Actor,
+{
+    /// Returns final cache key.
+    pub fn cache_key(&self) -> Result<String, CacheError> {
+        self.query.cache_key()
+    }
+}
+
+impl<A, M> Message for QueryCacheOutcome<A, M>
+where
+    A: Actor,
+    M: Message + Cacheable + Send,
+    M::Result: MessageResponse<A, M> + Send,
+{
+    type Result = Result<CacheOutcome<<M as Message>::Result>, CacheError>;
+}
+
+/// Actix message which only checks the cache and never polls the upstream actor.
+///
+/// [CacheActor](crate::CacheActor) responds with [CacheState] of cached data
+/// (actual, stale or missed) for the same cache key as the original [QueryCache] message has.
+/// If cache is disabled, [CacheState::Miss] is returned.
+pub struct PeekCache<A, M>
+where
+    M: Message + Cacheable + Send,
+    M::Result: MessageResponse<A, M> + Send,
+    A: Actor,
+{
+    pub(crate) query: QueryCache<A, M>,
+}
+
+impl<A, M> PeekCache<A, M>
+where
+    M: Message + Cacheable + Send,
+    M::Result: MessageResponse<A, M> + Send,
+    A: Actor,
+{
+    /// Returns final cache key.
+    pub fn cache_key(&self) -> Result<String, CacheError> {
+        self.query.cache_key()
+    }
+}
+
+impl<A, M> Message for PeekCache<A, M>
+where
+    A: Actor,
+    M: Message + Cacheable + Send,
+    M::Result: MessageResponse<A, M> + Send,
+{
+    type Result = Result<CacheState<<M as Message>::Result>, CacheError>;
+}
+
+/// Actix message requests counters of the write-behind queue of [CacheActor].
+///
+/// Returns `None` if the queue is not enabled with
+/// [CacheBuilder::with_write_behind](crate::CacheBuilder::with_write_behind).
+///
+/// [CacheActor]: crate::CacheActor
+#[derive(Debug, Clone, Copy)]
+pub struct GetWriteBehindStats;
+
+impl Message for GetWriteBehindStats {
+    type Result = Option<WriteBehindStats>;
+}
+
+/// Actix message waits until cache updates queued in the write-behind queue
+/// of [CacheActor] before this message are written to the backend.
+///
+/// Resolves immediately if the queue is not enabled.
+///
+/// [CacheActor]: crate::CacheActor
+#[derive(Debug, Clone, Copy)]
+pub struct FlushWriteBehind;
+
+impl Message for FlushWriteBehind {
+    type Result = ();
+}
+
+/// Current runtime settings of [CacheActor].
+///
+/// [CacheActor]: crate::CacheActor
+#[derive(Debug, Clone, MessageResponse)]
+pub struct CacheActorSettings {
+    /// Cache, stale and lock mechanics settings.
+    pub settings: CacheSettings,
+    /// Multiplier of TTL and stale TTL of all messages.
+    pub ttl_multiplier: f64,
+}
+
+/// Actix message requests current runtime settings of [CacheActor].
+///
+/// [CacheActor]: crate::CacheActor
+#[derive(Debug, Clone, Copy)]
+pub struct GetSettings;
+
+impl Message for GetSettings {
+    type Result = CacheActorSettings;
+}
+
+/// Actix message changes settings of [CacheActor] at runtime.
+///
+/// Settings which are `None` stay unchanged. Changes apply to messages received
+/// after this one and are not shared with other instances. Returns updated settings.
+///
+/// # Examples
+/// ```ignore
+/// // Bypass the cache for all messages.
+/// cache.send(UpdateSettings(SettingsOverride::default().disable())).await?;
+/// ```
+///
+/// [CacheActor]: crate::CacheActor
+#[derive(Debug, Clone)]
+pub struct UpdateSettings(pub SettingsOverride);
+
+impl Message for UpdateSettings {
+    type Result = CacheActorSettings;
+}
+
+/// Actix message changes the TTL multiplier of [CacheActor] at runtime.
+///
+/// The initial multiplier is set by [CacheBuilder::with_ttl_multiplier].
+/// Multiplier should be positive and finite, other values are ignored.
+/// Returns updated settings.
+///
+/// [CacheActor]: crate::CacheActor
+/// [CacheBuilder::with_ttl_multiplier]: crate::CacheBuilder::with_ttl_multiplier
+#[derive(Debug, Clone, Copy)]
+pub struct SetTtlMultiplier(pub f64);
+
+impl Message for SetTtlMultiplier {
+    type Result = CacheActorSettings;
+}
+
+/// Actix message requests cache keys starting with the prefix.
+///
+/// The prefix is appended to the namespace and the global version of [CacheActor],
+/// like `Upstream::Message::`. Returns a page of full backend keys sorted in the page,
+/// the next page is requested with the cursor of the previous one.
+/// Requires [Scan] message support by the backend.
+///
+/// [CacheActor]: crate::CacheActor
+/// [Scan]: hitbox_backend::Scan
+#[derive(Debug, Clone)]
+pub struct ListEntries {
+    /// Prefix of cache keys after the namespace.
+    pub prefix: String,
+    /// Cursor returned with the previous page, `None` starts from the beginning.
+    pub cursor: Option<String>,
+    /// Maximum number of keys in the page, `None` returns all remaining keys.
+    pub limit: Option<usize>,
+}
+
+impl Message for ListEntries {
+    type Result = Result<ScanPage, CacheError>;
+}
+
+/// Actix message requests usage statistics of cached values in the namespace of [CacheActor].
+///
+/// Requires [Stats] message support by the backend. Evictions are counted by the whole backend.
+///
+/// [CacheActor]: crate::CacheActor
+/// [Stats]: hitbox_backend::Stats
+#[derive(Debug, Clone, Copy)]
+pub struct GetBackendStats;
+
+impl Message for GetBackendStats {
+    type Result = Result<BackendStats, CacheError>;
+}
+
+/// Number and size of cached values of one message type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "admin", derive(serde::Serialize))]
+pub struct MessageStats {
+    /// Cache key part before the message version, like `Upstream::Message`.
+    pub message: String,
+    /// Number of cached values.
+    pub entries: u64,
+    /// Memory used by cached values in bytes, as reported by the backend.
+    pub bytes: u64,
+}
+
+/// Actix message requests number and size of cached values per message type.
+///
+/// Message types are taken from the keys in the namespace of [CacheActor]:
+/// everything before the version segment (`v0`) of the key. Keys without a version
+/// segment are grouped by the part before the last `::`.
+/// Keys are scanned once by pages and sized with one [KeySizes] message per page.
+/// Returns statistics sorted by message type.
+/// Requires [Scan] and [KeySizes] messages support by the backend.
+///
+/// [CacheActor]: crate::CacheActor
+/// [Scan]: hitbox_backend::Scan
+/// [KeySizes]: hitbox_backend::KeySizes
+#[derive(Debug, Clone, Copy)]
+pub struct GetMessageStats;
+
+impl Message for GetMessageStats {
+    type Result = Result<Vec<MessageStats>, CacheError>;
+}
+
+/// Cached value with its backend key and decoded metadata.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    /// Full backend key of the value.
+    pub key: String,
+    /// Size of the serialized value in bytes.
+    pub bytes: usize,
+    /// Decoded cached value.
+    pub record: CachedRecord,
+    /// Expiration metadata stored by the backend.
+    pub metadata: RecordMetadata,
+}
+
+impl CacheEntry {
+    /// Returns `true` if cached data is stale.
+    ///
+    /// Stale time stored by the backend is used if it's known,
+    /// otherwise it's taken from the decoded value.
+    pub fn is_stale(&self) -> bool {
+        self.metadata
+            .is_stale()
+            .unwrap_or_else(|| self.record.is_stale())
+    }
+}
+
+/// Actix message requests the cached value by full backend key,
+/// like keys returned by [ListEntries].
+///
+/// Returns `None` if the value is missing or the key is outside the namespace
+/// of [CacheActor]. Inspection doesn't extend the value expiration or change its
+/// eviction order. Requires [GetMetadata] and [Peek] messages support by the backend.
+///
+/// [CacheActor]: crate::CacheActor
+/// [GetMetadata]: hitbox_backend::GetMetadata
+/// [Peek]: hitbox_backend::Peek
+#[derive(Debug, Clone)]
+pub struct InspectEntry {
+    /// Full backend key of the value.
+    pub key: String,
+}
+
+impl Message for InspectEntry {
+    type Result = Result<Option<CacheEntry>, CacheError>;
+}
+
+/// Actix message deletes the cached value by full backend key,
+/// like keys returned by [ListEntries].
+///
+/// Returns `true` if the value was deleted, `false` if it was missing.
+/// Keys outside the namespace of [CacheActor] are never deleted.
+///
+/// [CacheActor]: crate::CacheActor
+#[derive(Debug, Clone)]
+pub struct DeleteEntry {
+    /// Full backend key of the value.
+    pub key: String,
+}
+
+impl Message for DeleteEntry {
+    type Result = Result<bool, CacheError>;
+}
+
+/// Actix message deletes all cached values listed by [ListEntries] with the same prefix.
+///
+/// Returns the number of deleted values.
+/// Requires [Scan] message support by the backend.
+///
+/// [Scan]: hitbox_backend::Scan
+#[derive(Debug, Clone)]
+pub struct DeleteEntries {
+    /// Prefix of cache keys after the namespace.
+    pub prefix: String,
+}
+
+impl Message for DeleteEntries {
+    type Result = Result<u64, CacheError>;
+}
+
+/// Returns message type part of the cache key without namespace.
+pub(crate) fn message_type(key: &str) -> &str {
+    let is_version = |segment: &str| {
+        segment.len() > 1
+            && segment.starts_with('v')
+            && segment[1..].bytes().all(|symbol| symbol.is_ascii_digit())
+    };
+    let mut offset = 0;
+    for (index, segment) in key.split("::").enumerate() {
+        if index > 0 && is_version(segment) {
+            return &key[..offset - 2];
+        }
+        offset += segment.len() + 2;
+    }
+    match key.rfind("::") {
+        Some(end) => &key[..end],
+        None => key,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{message_type, short_type_name};
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(short_type_name("app::actors::Storage"), "Storage");
+        assert_eq!(
+            short_type_name("app::Storage<alloc::string::String, i32>"),
+            "Storage<String, i32>"
+        );
+        assert_eq!(
+            short_type_name("app::Storage<(app::Key, &str)>"),
+            "Storage<(Key, &str)>"
+        );
+    }
+
+    #[test]
+    fn test_message_type() {
+        assert_eq!(
+            message_type("Upstream::Message::v0::id=1"),
+            "Upstream::Message"
+        );
+        assert_eq!(
+            message_type("Storage::db::Query::v12::2e3b8e3a8b1c9d6f"),
+            "Storage::db::Query"
+        );
+        assert_eq!(message_type("Upstream::Message::id=1"), "Upstream::Message");
+        assert_eq!(message_type("v1::key"), "v1");
+        assert_eq!(message_type("key"), "key");
+    }
+}
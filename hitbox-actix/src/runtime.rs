@@ -1,17 +1,33 @@
 //! [hitbox::runtime::RuntimeAdapter] implementation for Actix runtime.
+use std::time::Duration;
+
 use actix::dev::{MessageResponse, ToEnvelope};
 use actix::{Actor, Addr, Handler, Message};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use tracing::warn;
+use tracing::{debug, warn};
 
 use hitbox::response::CacheableResponse;
-use hitbox::runtime::{AdapterResult, EvictionPolicy, RuntimeAdapter, TtlSettings};
+use hitbox::runtime::{
+    AdapterResult, CircuitBreaker, CircuitOpenError, EvictionPolicy, RetryPolicy, RuntimeAdapter,
+    TtlSettings,
+};
 use hitbox::{CacheError, CacheState, Cacheable, CachedValue};
-use hitbox_backend::{Backend, Get, Set};
+use hitbox_backend::{Backend, BackendError, Bytes, Get, Set};
 
+use crate::writer::WriteQueue;
 use crate::QueryCache;
 
+/// Returns `true` if TTL values can be multiplied by `multiplier`.
+pub(crate) fn is_valid_ttl_multiplier(multiplier: f64) -> bool {
+    multiplier.is_finite() && multiplier > 0.0
+}
+
+/// Error returned when the upstream message was already consumed by previous polling.
+#[derive(Debug, thiserror::Error)]
+#[error("Message already sent to upstream")]
+pub struct MessageAlreadySent;
+
 /// [`RuntimeAdapter`] for Actix runtime.
 pub struct ActixAdapter<A, M, B>
 where
@@ -25,6 +41,12 @@ where
     cache_ttl: u32,
     cache_stale_ttl: u32,
     backend: Addr<B>,
+    backend_timeout: Option<Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+    upstream_timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    background_writes: bool,
+    write_queue: Option<WriteQueue>,
 }
 
 impl<A, M, B> ActixAdapter<A, M, B>
@@ -37,7 +59,7 @@ where
     /// Creates new instance of Actix runtime adapter.
     pub fn new(message: QueryCache<A, M>, backend: Addr<B>) -> Result<Self, CacheError> {
         let cache_key = message.cache_key()?;
-        let cache_stale_ttl = message.message.cache_ttl();
+        let cache_stale_ttl = message.message.cache_stale_ttl();
         let cache_ttl = message.message.cache_ttl();
         Ok(Self {
             message: Some(message),
@@ -45,8 +67,112 @@ where
             cache_key,
             cache_ttl,
             cache_stale_ttl,
+            backend_timeout: None,
+            circuit_breaker: None,
+            upstream_timeout: None,
+            retry_policy: RetryPolicy::default(),
+            background_writes: false,
+            write_queue: None,
         })
     }
+
+    /// Sets timeout for each cache backend operation.
+    pub fn with_backend_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.backend_timeout = timeout;
+        self
+    }
+
+    /// Sets circuit breaker which guards cache backend operations.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Option<CircuitBreaker>) -> Self {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    /// Sets timeout for each upstream polling attempt.
+    pub fn with_upstream_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.upstream_timeout = timeout;
+        self
+    }
+
+    /// Sets whether cache updates are sent to the backend without waiting for the response.
+    pub fn with_background_writes(mut self, background_writes: bool) -> Self {
+        self.background_writes = background_writes;
+        self
+    }
+
+    /// Sets write-behind queue which receives cache updates instead of the backend.
+    pub(crate) fn with_write_queue(mut self, write_queue: Option<WriteQueue>) -> Self {
+        self.write_queue = write_queue;
+        self
+    }
+
+    /// Multiplies TTL and stale TTL of the message by the multiplier.
+    ///
+    /// Non-zero values stay at least one second long, invalid multipliers are ignored.
+    pub fn with_ttl_multiplier(mut self, multiplier: f64) -> Self {
+        if !is_valid_ttl_multiplier(multiplier) {
+            warn!("Invalid cache TTL multiplier {} ignored", multiplier);
+            return self;
+        }
+        let scale = |ttl: u32| match ttl {
+            0 => 0,
+            ttl => ((f64::from(ttl) * multiplier).round() as u32).max(1),
+        };
+        self.cache_ttl = scale(self.cache_ttl);
+        self.cache_stale_ttl = scale(self.cache_stale_ttl);
+        self
+    }
+
+    /// Prepends the namespace prefix to the cache key.
+    pub fn with_key_prefix(mut self, prefix: &str) -> Self {
+        self.cache_key = format!("{}{}", prefix, self.cache_key);
+        self
+    }
+
+    /// Sets retry policy for failed upstream polling attempts.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+}
+
+/// Sends message to cache backend with respect to timeout and circuit breaker.
+///
+/// Mailbox errors (including timeouts) are treated as connection errors,
+/// so slow backend opens circuit breaker the same way as unavailable one.
+pub(crate) async fn send_to_backend<B, M, R>(
+    backend: Addr<B>,
+    message: M,
+    timeout: Option<Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+) -> Result<R, BackendError>
+where
+    B: Backend + Handler<M>,
+    <B as Actor>::Context: ToEnvelope<B, M>,
+    M: Message<Result = Result<R, BackendError>> + Send + 'static,
+    R: Send,
+{
+    if let Some(circuit_breaker) = &circuit_breaker {
+        if !circuit_breaker.is_available() {
+            return Err(BackendError::ConnectionError(Box::new(CircuitOpenError)));
+        }
+    }
+    let request = backend.send(message);
+    let request = match timeout {
+        Some(timeout) => request.timeout(timeout),
+        None => request,
+    };
+    let result = request
+        .await
+        .map_err(|error| BackendError::ConnectionError(Box::new(error)))
+        .and_then(|result| result);
+    if let Some(circuit_breaker) = circuit_breaker {
+        match result {
+            Err(BackendError::ConnectionError(_)) => circuit_breaker.record_failure(),
+            _ => circuit_breaker.record_success(),
+        }
+    }
+    result
 }
 
 impl<A, M, T, B, U> RuntimeAdapter for ActixAdapter<A, M, B>
@@ -64,41 +190,94 @@ where
 
     fn poll_upstream(&mut self) -> AdapterResult<Self::UpstreamResult> {
         let message = self.message.take();
+        let timeout = self.upstream_timeout;
+        let retry_policy = self.retry_policy.clone();
         Box::pin(async move {
-            let message = message.ok_or_else(|| {
-                CacheError::CacheKeyGenerationError("Message already sent to upstream".to_owned())
-            })?;
-            Ok(message.upstream.send(message.message).await?)
+            let QueryCache {
+                upstream,
+                message,
+                clone_message,
+                ..
+            } = message.ok_or(CacheError::UpstreamError(Box::new(MessageAlreadySent)))?;
+            if clone_message.is_none() && retry_policy.max_retries > 0 {
+                debug!("Upstream retries are configured, but the message isn't retryable");
+            }
+            let mut message = message;
+            let mut attempt = 0;
+            loop {
+                let next_message = clone_message
+                    .filter(|_| attempt < retry_policy.max_retries)
+                    .map(|clone_message| clone_message(&message));
+                let request = upstream.send(message);
+                let request = match timeout {
+                    Some(timeout) => request.timeout(timeout),
+                    None => request,
+                };
+                match (request.await, next_message) {
+                    (Ok(result), _) => return Ok(result),
+                    (Err(error), Some(next_message)) => {
+                        warn!("Upstream error {}, retry attempt {}", error, attempt + 1);
+                        actix::clock::sleep(retry_policy.delay(attempt)).await;
+                        attempt += 1;
+                        message = next_message;
+                    }
+                    (Err(error), None) => return Err(error.into()),
+                }
+            }
         })
     }
 
     fn poll_cache(&self) -> AdapterResult<CacheState<Self::UpstreamResult>> {
         let backend = self.backend.clone();
         let cache_key = self.cache_key.clone();
+        let timeout = self.backend_timeout;
+        let circuit_breaker = self.circuit_breaker.clone();
         Box::pin(async move {
-            let cached_value = backend.send(Get { key: cache_key }).await??;
-            CacheState::from_bytes(cached_value.as_ref())
+            let get = Get {
+                key: cache_key.clone(),
+            };
+            let cached_value = send_to_backend(backend, get, timeout, circuit_breaker).await?;
+            CacheState::from_bytes(&cache_key, cached_value.as_deref())
         })
     }
 
+    /// Serializes the value and writes it to the backend.
+    ///
+    /// Serialization always happens on the request path, because the upstream value
+    /// itself is returned to the caller. With write-behind queue only the serialized
+    /// value is passed to the queue, otherwise with background writes the write is
+    /// spawned without waiting for the backend response.
     fn update_cache(&self, cached_value: &CachedValue<Self::UpstreamResult>) -> AdapterResult<()> {
         let serialized = cached_value.serialize();
         let ttl = self.cache_ttl;
+        let stale_ttl = self.cache_stale_ttl;
         let backend = self.backend.clone();
         let cache_key = self.cache_key.clone();
-        Box::pin(async move {
-            let serialized = serialized?;
-            let _ = backend
-                .send(Set {
-                    key: cache_key,
-                    value: serialized,
-                    ttl: Some(ttl),
-                })
+        let timeout = self.backend_timeout;
+        let circuit_breaker = self.circuit_breaker.clone();
+        let set = serialized.map(|serialized| Set {
+            key: cache_key,
+            value: Bytes::from(serialized),
+            ttl: Some(ttl),
+            stale_ttl: Some(stale_ttl),
+        });
+        let write = move |set| async move {
+            let _ = send_to_backend(backend, set, timeout, circuit_breaker)
                 .await
-                .map_err(|error| warn!("Updating Cache Error {}", error))
-                .and_then(|value| value.map_err(|error| warn!("Updating Cache Error. {}", error)));
-            Ok(())
-        })
+                .map_err(|error| warn!("Updating Cache Error. {}", error));
+        };
+        match (set, &self.write_queue) {
+            (Ok(set), Some(write_queue)) => write_queue.push(set),
+            (Ok(set), None) if self.background_writes => {
+                actix::spawn(write(set));
+                Box::pin(async { Ok(()) })
+            }
+            (Ok(set), None) => Box::pin(async move {
+                write(set).await;
+                Ok(())
+            }),
+            (Err(error), _) => Box::pin(async { Err(error) }),
+        }
     }
     fn eviction_settings(&self) -> EvictionPolicy {
         let ttl_settings = TtlSettings {
@@ -0,0 +1,229 @@
+//! Write-behind queue of cache updates.
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix::dev::ToEnvelope;
+use actix::{Actor, Addr};
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+use hitbox::runtime::{AdapterResult, CircuitBreaker};
+use hitbox_backend::{Backend, Set};
+
+use crate::runtime::send_to_backend;
+
+/// Behavior of the write-behind queue when it's full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "lowercase"))]
+pub enum WriteOverflow {
+    /// Drop the cache update and count it in [WriteBehindStats::dropped].
+    Drop,
+    /// Wait for a free slot in the queue before returning the upstream value.
+    Wait,
+}
+
+/// Write-behind queue configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct WriteBehind {
+    /// Maximum number of cache updates waiting for the backend write.
+    pub capacity: usize,
+    /// Behavior of the queue when it's full.
+    pub overflow: WriteOverflow,
+}
+
+/// Snapshot of write-behind queue counters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "admin", derive(serde::Serialize))]
+pub struct WriteBehindStats {
+    /// Cache updates accepted by the queue.
+    pub enqueued: u64,
+    /// Cache updates successfully written to the backend.
+    pub written: u64,
+    /// Cache updates failed by the backend.
+    pub failed: u64,
+    /// Cache updates dropped because the queue was full.
+    pub dropped: u64,
+    /// Cache updates dropped because the queue worker was stopped.
+    pub closed: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    enqueued: AtomicU64,
+    written: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+    closed: AtomicU64,
+}
+
+/// Item of the write-behind queue.
+#[derive(Debug)]
+enum Command {
+    /// Write the cache update to the backend.
+    Write(Set),
+    /// Notify the sender when all previous commands are processed.
+    Flush(oneshot::Sender<()>),
+}
+
+/// Worker of the write-behind queue, it should be spawned inside the actix system.
+pub(crate) type WriteWorker = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Handle of the write-behind queue.
+#[derive(Debug, Clone)]
+pub(crate) struct WriteQueue {
+    sender: mpsc::Sender<Command>,
+    overflow: WriteOverflow,
+    counters: Arc<Counters>,
+}
+
+impl WriteQueue {
+    /// Creates the queue and its worker which writes queued cache updates
+    /// to the backend one by one.
+    ///
+    /// Cache updates are buffered until the worker is spawned.
+    pub(crate) fn new<B>(
+        config: WriteBehind,
+        backend: Addr<B>,
+        timeout: Option<Duration>,
+        circuit_breaker: Option<CircuitBreaker>,
+    ) -> (Self, WriteWorker)
+    where
+        B: Backend,
+        <B as Actor>::Context: ToEnvelope<B, Set>,
+    {
+        let (sender, mut receiver) = mpsc::channel(config.capacity.max(1));
+        let counters = Arc::new(Counters::default());
+        let worker_counters = counters.clone();
+        let worker = Box::pin(async move {
+            while let Some(command) = receiver.recv().await {
+                let set = match command {
+                    Command::Write(set) => set,
+                    Command::Flush(sender) => {
+                        let _ = sender.send(());
+                        continue;
+                    }
+                };
+                let result =
+                    send_to_backend(backend.clone(), set, timeout, circuit_breaker.clone()).await;
+                match result {
+                    Ok(_) => worker_counters.written.fetch_add(1, Ordering::Relaxed),
+                    Err(error) => {
+                        warn!("Updating Cache Error. {}", error);
+                        worker_counters.failed.fetch_add(1, Ordering::Relaxed)
+                    }
+                };
+            }
+        });
+        let queue = Self {
+            sender,
+            overflow: config.overflow,
+            counters,
+        };
+        (queue, worker)
+    }
+
+    /// Puts the cache update into the queue according to the overflow behavior.
+    pub(crate) fn push(&self, set: Set) -> AdapterResult<()> {
+        let queue = self.clone();
+        Box::pin(async move {
+            let command = Command::Write(set);
+            let result = match queue.overflow {
+                WriteOverflow::Drop => {
+                    queue.sender.try_send(command).map_err(|error| match error {
+                        mpsc::error::TrySendError::Full(_) => false,
+                        mpsc::error::TrySendError::Closed(_) => true,
+                    })
+                }
+                WriteOverflow::Wait => queue.sender.send(command).await.map_err(|_| true),
+            };
+            match result {
+                Ok(()) => queue.counters.enqueued.fetch_add(1, Ordering::Relaxed),
+                Err(false) => {
+                    warn!("Write-behind queue is full, cache update dropped");
+                    queue.counters.dropped.fetch_add(1, Ordering::Relaxed)
+                }
+                Err(true) => {
+                    warn!("Write-behind queue is stopped, cache update dropped");
+                    queue.counters.closed.fetch_add(1, Ordering::Relaxed)
+                }
+            };
+            Ok(())
+        })
+    }
+
+    /// Waits until cache updates queued before this call are processed.
+    pub(crate) async fn flush(&self) {
+        let (sender, receiver) = oneshot::channel();
+        if self.sender.send(Command::Flush(sender)).await.is_ok() {
+            let _ = receiver.await;
+        }
+    }
+
+    /// Returns current values of queue counters.
+    pub(crate) fn stats(&self) -> WriteBehindStats {
+        WriteBehindStats {
+            enqueued: self.counters.enqueued.load(Ordering::Relaxed),
+            written: self.counters.written.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            closed: self.counters.closed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hitbox::dev::mock_backend::backend::MockBackend;
+    use hitbox_backend::Bytes;
+
+    fn set(key: &str) -> Set {
+        Set {
+            key: key.to_owned(),
+            value: Bytes::from_static(b"value"),
+            ttl: None,
+            stale_ttl: None,
+        }
+    }
+
+    fn queue(capacity: usize) -> (WriteQueue, WriteWorker) {
+        let backend = MockBackend::new().start();
+        let config = WriteBehind {
+            capacity,
+            overflow: WriteOverflow::Drop,
+        };
+        WriteQueue::new(config, backend, None, None)
+    }
+
+    #[actix::test]
+    async fn test_drop_on_overflow() {
+        let (queue, worker) = queue(1);
+        queue.push(set("first")).await.unwrap();
+        queue.push(set("second")).await.unwrap();
+        assert_eq!(queue.stats().enqueued, 1);
+        assert_eq!(queue.stats().dropped, 1);
+
+        actix::spawn(worker);
+        queue.flush().await;
+        assert_eq!(queue.stats().written, 1);
+    }
+
+    #[actix::test]
+    async fn test_drop_on_stopped_worker() {
+        let (queue, worker) = queue(10);
+        drop(worker);
+        queue.push(set("first")).await.unwrap();
+        queue.flush().await;
+        assert_eq!(
+            queue.stats(),
+            WriteBehindStats {
+                closed: 1,
+                ..WriteBehindStats::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "admin")]
+use actix::prelude::*;
+use actix_web::http::StatusCode;
+use actix_web::{test, App};
+use hitbox::dev::mock_backend::backend::MockBackend;
+use hitbox_actix::admin_scope;
+use hitbox_actix::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+
+struct Upstream;
+
+impl Actor for Upstream {
+    type Context = Context<Self>;
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "i32")]
+struct Ping {
+    id: i32,
+}
+
+impl Handler<Ping> for Upstream {
+    type Result = i32;
+
+    fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+        msg.id
+    }
+}
+
+async fn cache_with_values() -> Addr<CacheActor<MockBackend>> {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_namespace("svc")
+        .finish(backend)
+        .start();
+    let upstream = Upstream.start();
+    for id in 0..3 {
+        cache
+            .send(Ping { id }.into_cache(&upstream))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    cache
+}
+
+#[actix::test]
+async fn test_inspect_entry() {
+    let cache = cache_with_values().await;
+    let app = test::init_service(App::new().service(admin_scope("/cache", cache))).await;
+
+    let request = test::TestRequest::get()
+        .uri("/cache/entries?prefix=Upstream::Ping::&limit=2")
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(page["keys"].as_array().unwrap().len(), 2);
+    assert_eq!(page["keys"][0], "svc::Upstream::Ping::v0::id=0");
+    assert_eq!(page["cursor"], "svc::Upstream::Ping::v0::id=1");
+
+    let request = test::TestRequest::get()
+        .uri(
+            "/cache/entries?prefix=Upstream::Ping::&limit=2&cursor=svc::Upstream::Ping::v0::id%3D1",
+        )
+        .to_request();
+    let page: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(page["keys"][0], "svc::Upstream::Ping::v0::id=2");
+    assert_eq!(page["cursor"], Value::Null);
+
+    let request = test::TestRequest::get()
+        .uri("/cache/entry?key=svc::Upstream::Ping::v0::id%3D1")
+        .to_request();
+    let entry: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(entry["key"], "svc::Upstream::Ping::v0::id=1");
+    assert_eq!(entry["data"], 1);
+    assert_eq!(entry["stale"], false);
+    assert_eq!(entry["schema_version"], 0);
+    assert!(entry["age_seconds"].is_number());
+    assert_eq!(entry["ttl_seconds"], 60);
+
+    let request = test::TestRequest::get()
+        .uri("/cache/entry?key=missing")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix::test]
+async fn test_delete_entries() {
+    let cache = cache_with_values().await;
+    let app = test::init_service(App::new().service(admin_scope("/cache", cache))).await;
+
+    let request = test::TestRequest::delete()
+        .uri("/cache/entry?key=svc::Upstream::Ping::v0::id%3D0")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let request = test::TestRequest::delete()
+        .uri("/cache/entries")
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::delete()
+        .uri("/cache/entries?prefix=Upstream::")
+        .to_request();
+    let response: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(response["deleted"], 2);
+
+    let request = test::TestRequest::get().uri("/cache/stats").to_request();
+    let stats: Value = test::call_and_read_body_json(&app, request).await;
+    assert_eq!(stats["backend"]["entries"], 0);
+    assert_eq!(stats["messages"], Value::Array(Vec::new()));
+    assert_eq!(stats["write_behind"], Value::Null);
+}
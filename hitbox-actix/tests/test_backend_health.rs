@@ -0,0 +1,159 @@
+use actix::prelude::*;
+use hitbox::dev::{
+    Backend, BackendError, Bytes, Delete, DeleteStatus, Get, Lock, LockStatus, Ping as HealthCheck,
+    Set,
+};
+use hitbox_actix::prelude::*;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+struct UpstreamActor;
+
+impl Actor for UpstreamActor {
+    type Context = Context<Self>;
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "i32")]
+struct Ping {
+    id: i32,
+}
+
+impl Handler<Ping> for UpstreamActor {
+    type Result = i32;
+
+    fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+        msg.id
+    }
+}
+
+/// Backend which answers after `delay` or fails with connection error.
+struct BrokenBackend {
+    delay: Option<Duration>,
+    calls: u32,
+}
+
+fn connection_refused() -> BackendError {
+    BackendError::ConnectionError(Box::new(std::io::Error::from(
+        std::io::ErrorKind::ConnectionRefused,
+    )))
+}
+
+impl Actor for BrokenBackend {
+    type Context = Context<Self>;
+}
+
+impl Backend for BrokenBackend {
+    type Actor = Self;
+    type Context = Context<Self>;
+}
+
+impl Handler<Get> for BrokenBackend {
+    type Result = ResponseFuture<Result<Option<Bytes>, BackendError>>;
+
+    fn handle(&mut self, _: Get, _: &mut Self::Context) -> Self::Result {
+        self.calls += 1;
+        let delay = self.delay;
+        Box::pin(async move {
+            match delay {
+                Some(delay) => {
+                    actix::clock::sleep(delay).await;
+                    Ok(None)
+                }
+                None => Err(connection_refused()),
+            }
+        })
+    }
+}
+
+impl Handler<Set> for BrokenBackend {
+    type Result = Result<String, BackendError>;
+
+    fn handle(&mut self, _: Set, _: &mut Self::Context) -> Self::Result {
+        self.calls += 1;
+        match self.delay {
+            Some(_) => Ok("OK".to_owned()),
+            None => Err(connection_refused()),
+        }
+    }
+}
+
+impl Handler<Delete> for BrokenBackend {
+    type Result = Result<DeleteStatus, BackendError>;
+
+    fn handle(&mut self, _: Delete, _: &mut Self::Context) -> Self::Result {
+        Ok(DeleteStatus::Missing)
+    }
+}
+
+impl Handler<Lock> for BrokenBackend {
+    type Result = Result<LockStatus, BackendError>;
+
+    fn handle(&mut self, _: Lock, _: &mut Self::Context) -> Self::Result {
+        Ok(LockStatus::Acquired)
+    }
+}
+
+impl Handler<HealthCheck> for BrokenBackend {
+    type Result = Result<(), BackendError>;
+
+    fn handle(&mut self, _: HealthCheck, _: &mut Self::Context) -> Self::Result {
+        Ok(())
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "u32")]
+struct GetCalls;
+
+impl Handler<GetCalls> for BrokenBackend {
+    type Result = u32;
+
+    fn handle(&mut self, _: GetCalls, _: &mut Self::Context) -> Self::Result {
+        self.calls
+    }
+}
+
+#[actix::test]
+async fn test_backend_timeout() {
+    let backend = BrokenBackend {
+        delay: Some(Duration::from_secs(10)),
+        calls: 0,
+    }
+    .start();
+    let cache = CacheActor::builder()
+        .with_backend_timeout(Duration::from_millis(50))
+        .finish(backend)
+        .start();
+    let upstream = UpstreamActor.start();
+    let started = Instant::now();
+    let res = cache.send(Ping { id: 42 }.into_cache(&upstream)).await;
+    assert_eq!(res.unwrap().unwrap(), 42);
+    assert!(started.elapsed() < Duration::from_secs(1));
+}
+
+#[actix::test]
+async fn test_circuit_breaker_skips_backend() {
+    let backend = BrokenBackend {
+        delay: None,
+        calls: 0,
+    }
+    .start();
+    let cache = CacheActor::builder()
+        .with_circuit_breaker(2, Duration::from_secs(60))
+        .finish(backend.clone())
+        .start();
+    let upstream = UpstreamActor.start();
+    for id in 0..5 {
+        let res = cache.send(Ping { id }.into_cache(&upstream)).await;
+        assert_eq!(res.unwrap().unwrap(), id);
+    }
+    // Failed `Get` and `Set` open the breaker, then backend is skipped.
+    assert_eq!(backend.send(GetCalls).await.unwrap(), 2);
+}
+
+#[actix::test]
+async fn test_mock_backend_ping() {
+    let backend = hitbox::dev::mock_backend::backend::MockBackend::new().start();
+    assert!(backend.send(HealthCheck).await.unwrap().is_ok());
+}
@@ -60,3 +60,75 @@ async fn test_final_cache_key() {
         "NextUpstream::Message::v0::id=28&alias=cow+level"
     );
 }
+
+struct NamedUpstream;
+
+impl Actor for NamedUpstream {
+    type Context = Context<Self>;
+}
+
+impl Handler<Message> for NamedUpstream {
+    type Result = ResponseFuture<String>;
+
+    fn handle(&mut self, _msg: Message, _: &mut Self::Context) -> Self::Result {
+        Box::pin(async { "NamedUpstream".to_owned() })
+    }
+}
+
+impl CacheableUpstream for NamedUpstream {
+    fn upstream_name() -> &'static str {
+        "named"
+    }
+}
+
+struct GenericUpstream<T>(std::marker::PhantomData<T>);
+
+impl<T: Unpin + 'static> Actor for GenericUpstream<T> {
+    type Context = Context<Self>;
+}
+
+impl<T: Unpin + 'static> Handler<Message> for GenericUpstream<T> {
+    type Result = ResponseFuture<String>;
+
+    fn handle(&mut self, _msg: Message, _: &mut Self::Context) -> Self::Result {
+        Box::pin(async { "GenericUpstream".to_owned() })
+    }
+}
+
+#[actix::test]
+async fn test_named_upstream_cache_key() {
+    let upstream = NamedUpstream.start();
+    let message = Message {
+        id: 42,
+        alias: "test".to_owned(),
+    }
+    .into_named_cache(&upstream);
+    assert_eq!(
+        message.cache_key().unwrap().as_str(),
+        "named::Message::v0::id=42&alias=test"
+    );
+    let message = Message {
+        id: 42,
+        alias: "test".to_owned(),
+    }
+    .into_cache(&upstream)
+    .with_upstream_name("explicit");
+    assert_eq!(
+        message.cache_key().unwrap().as_str(),
+        "explicit::Message::v0::id=42&alias=test"
+    );
+}
+
+#[actix::test]
+async fn test_generic_upstream_cache_key() {
+    let upstream = GenericUpstream::<String>(std::marker::PhantomData).start();
+    let message = Message {
+        id: 42,
+        alias: "test".to_owned(),
+    }
+    .into_cache(&upstream);
+    assert_eq!(
+        message.cache_key().unwrap().as_str(),
+        "GenericUpstream<String>::Message::v0::id=42&alias=test"
+    );
+}
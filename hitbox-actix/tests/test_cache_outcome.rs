@@ -0,0 +1,81 @@
+use actix::prelude::*;
+use hitbox::dev::mock_backend::backend::MockBackend;
+use hitbox_actix::prelude::*;
+use serde::Serialize;
+
+struct Upstream;
+
+impl Actor for Upstream {
+    type Context = Context<Self>;
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "i32")]
+struct Ping {
+    id: i32,
+}
+
+impl Handler<Ping> for Upstream {
+    type Result = i32;
+
+    fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+        msg.id
+    }
+}
+
+#[actix::test]
+async fn test_outcome_miss_then_hit() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend).start();
+    let upstream = Upstream.start();
+
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::Miss);
+    assert!(outcome.age().is_none());
+
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::Hit);
+    assert!(outcome.age().unwrap() >= chrono::Duration::zero());
+    assert!(outcome.expired.unwrap() > chrono::Utc::now());
+}
+
+#[actix::test]
+async fn test_outcome_cache_disabled() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().disable().finish(backend).start();
+    let upstream = Upstream.start();
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.into_inner(), 42);
+}
+
+#[actix::test]
+async fn test_outcome_key_too_long() {
+    let backend = MockBackend {
+        max_key_length: Some(8),
+        ..MockBackend::new()
+    }
+    .start();
+    let cache = CacheActor::builder().finish(backend).start();
+    let upstream = Upstream.start();
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::Error);
+}
@@ -0,0 +1,141 @@
+#![cfg(feature = "config")]
+use std::time::Duration;
+
+use actix::prelude::*;
+use hitbox::dev::mock_backend::backend::MockBackend;
+use hitbox::settings::Status;
+use hitbox_actix::config::CacheConfig;
+use hitbox_actix::prelude::*;
+
+#[test]
+fn test_default_config() {
+    let config = CacheConfig::from_toml("").unwrap();
+    assert!(config.enabled);
+    assert!(config.stale);
+    assert_eq!(config.ttl_multiplier, 1.0);
+    assert!(config.backend_timeout.is_none());
+    assert!(config.write_behind.is_none());
+}
+
+#[test]
+fn test_toml_config() {
+    let config = CacheConfig::from_toml(
+        r#"
+        stale = false
+        namespace = "billing:prod"
+        namespace_version = 3
+        backend_timeout = "50ms"
+
+        [circuit_breaker]
+        failure_threshold = 5
+        reset_timeout = "10s"
+
+        [write_behind]
+        capacity = 100
+        overflow = "wait"
+
+        [redis]
+        url = "redis://cache.local/1"
+        "#,
+    )
+    .unwrap();
+    assert!(!config.stale);
+    assert_eq!(config.namespace.as_deref(), Some("billing:prod"));
+    assert_eq!(config.namespace_version, Some(3));
+    assert_eq!(config.backend_timeout, Some(Duration::from_millis(50)));
+    let breaker = config.circuit_breaker.unwrap();
+    assert_eq!(breaker.failure_threshold, 5);
+    assert_eq!(breaker.reset_timeout, Duration::from_secs(10));
+    let write_behind = config.write_behind.unwrap();
+    assert_eq!(write_behind.capacity, 100);
+    assert_eq!(write_behind.overflow, WriteOverflow::Wait);
+    #[cfg(feature = "redis")]
+    assert_eq!(config.redis.url, "redis://cache.local/1");
+}
+
+#[test]
+fn test_yaml_config() {
+    let config = CacheConfig::from_yaml(
+        r#"
+        background_writes: true
+        ttl_multiplier: 0.5
+        upstream_retries:
+          max_retries: 2
+          backoff: 100ms
+        "#,
+    )
+    .unwrap();
+    assert!(config.background_writes);
+    assert_eq!(config.ttl_multiplier, 0.5);
+    let retries = config.upstream_retries.unwrap();
+    assert_eq!(retries.max_retries, 2);
+    assert_eq!(retries.backoff, Duration::from_millis(100));
+}
+
+#[test]
+fn test_env_config() {
+    std::env::set_var("HITBOX_TEST_ENABLED", "false");
+    std::env::set_var("HITBOX_TEST_UPSTREAM_TIMEOUT", "2s");
+    std::env::set_var("HITBOX_TEST_REDIS__MAX_KEY_LENGTH", "512");
+    let config = CacheConfig::from_env("HITBOX_TEST").unwrap();
+    assert!(!config.enabled);
+    assert_eq!(config.upstream_timeout, Some(Duration::from_secs(2)));
+    #[cfg(feature = "redis")]
+    assert_eq!(config.redis.max_key_length, Some(512));
+}
+
+#[test]
+fn test_invalid_config() {
+    assert!(CacheConfig::from_toml("backend_timeout = \"soon\"").is_err());
+}
+
+#[actix::test]
+async fn test_config_builder() {
+    let config = CacheConfig::from_toml(
+        r#"
+        stale = false
+        ttl_multiplier = 2.0
+        "#,
+    )
+    .unwrap();
+    let backend = MockBackend::new().start();
+    let cache = config.builder().finish(backend).start();
+    let settings = cache.send(GetSettings).await.unwrap();
+    assert!(matches!(settings.settings.cache, Status::Enabled));
+    assert!(matches!(settings.settings.stale, Status::Disabled));
+    assert!(matches!(settings.settings.lock, Status::Disabled));
+    assert_eq!(settings.ttl_multiplier, 2.0);
+}
+
+#[cfg(feature = "redis")]
+#[test]
+fn test_redis_config() {
+    let config = CacheConfig::from_yaml(
+        r#"
+        redis:
+          url: redis://cache.local/
+          username: hitbox
+          password: secret
+          database: 2
+          connect_timeout: 1s
+          response_timeout: 200ms
+          pool_size: 4
+          storage: hash
+          sliding_ttl: 5m
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.redis.username.as_deref(), Some("hitbox"));
+    assert_eq!(config.redis.password.as_deref(), Some("secret"));
+    assert!(!format!("{:?}", config).contains("secret"));
+    assert_eq!(config.redis.database, Some(2));
+    assert_eq!(config.redis.connect_timeout, Some(Duration::from_secs(1)));
+    assert_eq!(
+        config.redis.response_timeout,
+        Some(Duration::from_millis(200))
+    );
+    assert_eq!(config.redis.pool_size, 4);
+    assert_eq!(config.redis.storage, hitbox_redis::RedisStorage::Hash);
+    assert_eq!(config.redis.sliding_ttl, Some(Duration::from_secs(300)));
+    assert!(config.redis.builder().is_ok());
+}
@@ -0,0 +1,203 @@
+use actix::prelude::*;
+use hitbox::dev::mock_backend::backend::{
+    GetMessages, GetMessagesResult, MockBackend, MockMessage,
+};
+use hitbox::dev::{Bytes, GetMetadata, Peek, Set};
+use hitbox_actix::prelude::*;
+use serde::Serialize;
+
+struct Upstream;
+
+impl Actor for Upstream {
+    type Context = Context<Self>;
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "i32")]
+struct Ping {
+    id: i32,
+}
+
+impl Handler<Ping> for Upstream {
+    type Result = i32;
+
+    fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+        msg.id
+    }
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "String")]
+struct Echo {
+    text: String,
+}
+
+impl Handler<Echo> for Upstream {
+    type Result = String;
+
+    fn handle(&mut self, msg: Echo, _: &mut Self::Context) -> Self::Result {
+        msg.text
+    }
+}
+
+async fn fill_cache(backend: &Addr<MockBackend>) -> Addr<CacheActor<MockBackend>> {
+    let upstream = Upstream.start();
+    let other = CacheActor::builder()
+        .with_namespace("other")
+        .finish(backend.clone())
+        .start();
+    other
+        .send(Ping { id: 1 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let cache = CacheActor::builder()
+        .with_namespace("svc")
+        .finish(backend.clone())
+        .start();
+    for id in 0..2 {
+        cache
+            .send(Ping { id }.into_cache(&upstream))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    cache
+        .send(
+            Echo {
+                text: "hello".to_owned(),
+            }
+            .into_cache(&upstream),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    cache
+}
+
+#[actix::test]
+async fn test_list_entries() {
+    let backend = MockBackend::new().start();
+    let cache = fill_cache(&backend).await;
+
+    let page = cache
+        .send(ListEntries {
+            prefix: "Upstream::Ping::".to_owned(),
+            cursor: None,
+            limit: None,
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        page.keys,
+        vec![
+            "svc::Upstream::Ping::v0::id=0".to_owned(),
+            "svc::Upstream::Ping::v0::id=1".to_owned(),
+        ]
+    );
+    assert_eq!(page.cursor, None);
+
+    let mut keys = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = cache
+            .send(ListEntries {
+                prefix: String::new(),
+                cursor,
+                limit: Some(2),
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(page.keys.len() <= 2);
+        keys.extend(page.keys);
+        cursor = page.cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(keys.len(), 3);
+}
+
+#[actix::test]
+async fn test_message_stats() {
+    let backend = MockBackend::new().start();
+    let cache = fill_cache(&backend).await;
+    // Message type nested into the other one isn't counted twice.
+    backend
+        .send(Set {
+            key: "svc::Upstream::Ping::Nested::v0::id=0".to_owned(),
+            value: Bytes::from_static(b"value"),
+            ttl: None,
+            stale_ttl: None,
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let stats = cache.send(GetMessageStats).await.unwrap().unwrap();
+    let messages = stats
+        .iter()
+        .map(|stats| (stats.message.as_str(), stats.entries))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            ("Upstream::Echo", 1),
+            ("Upstream::Ping", 2),
+            ("Upstream::Ping::Nested", 1)
+        ]
+    );
+    assert!(stats.iter().all(|stats| stats.bytes > 0));
+    assert_eq!(stats[2].bytes, 42);
+
+    // Values of the other namespace aren't counted.
+    let total = cache.send(GetBackendStats).await.unwrap().unwrap();
+    assert_eq!(total.entries, 4);
+    assert_eq!(total.evictions, None);
+}
+
+#[actix::test]
+async fn test_entry_outside_namespace() {
+    let backend = MockBackend::new().start();
+    let cache = fill_cache(&backend).await;
+    let key = "other::Upstream::Ping::v0::id=1".to_owned();
+
+    let entry = cache
+        .send(InspectEntry { key: key.clone() })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(entry.is_none());
+    let deleted = cache
+        .send(DeleteEntry { key: key.clone() })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!deleted);
+
+    let other = CacheActor::builder()
+        .with_namespace("other")
+        .finish(backend.clone())
+        .start();
+    let GetMessagesResult(sent) = backend.send(GetMessages).await.unwrap();
+    let entry = other
+        .send(InspectEntry { key: key.clone() })
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.record.data, 1);
+
+    // Inspection reads the value without Get, which may extend its TTL.
+    let GetMessagesResult(messages) = backend.send(GetMessages).await.unwrap();
+    assert_eq!(
+        messages[sent.len()..],
+        [
+            MockMessage::GetMetadata(GetMetadata { key: key.clone() }),
+            MockMessage::Peek(Peek { key }),
+        ]
+    );
+}
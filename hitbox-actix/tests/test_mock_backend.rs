@@ -61,3 +61,126 @@ async fn test_mock_backend() {
         }),]
     );
 }
+
+#[actix::test]
+async fn test_background_writes() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_background_writes()
+        .finish(backend.clone())
+        .start();
+    let upstream = UpstreamActor.start();
+    let pong = cache
+        .send(Ping { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(pong.id, 42);
+
+    actix::clock::sleep(std::time::Duration::from_millis(10)).await;
+    let messages = backend.send(GetMessages).await.unwrap().0;
+    match &messages[..] {
+        [MockMessage::Get(_), MockMessage::Set(set)] => {
+            assert_eq!(set.key, "UpstreamActor::Ping::42");
+            assert!(!set.value.is_empty());
+        }
+        messages => panic!("Unexpected backend messages {:?}", messages),
+    }
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.status, CacheStatus::Hit);
+    assert_eq!(outcome.result.id, 42);
+}
+
+#[actix::test]
+async fn test_write_behind() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_write_behind(10, WriteOverflow::Drop)
+        .finish(backend.clone())
+        .start();
+    let upstream = UpstreamActor.start();
+    for id in 0..3 {
+        let pong = cache
+            .send(Ping { id }.into_cache(&upstream))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(pong.id, id);
+    }
+
+    cache.send(FlushWriteBehind).await.unwrap();
+    let stats = cache.send(GetWriteBehindStats).await.unwrap();
+    assert_eq!(
+        stats,
+        Some(WriteBehindStats {
+            enqueued: 3,
+            written: 3,
+            failed: 0,
+            dropped: 0,
+            closed: 0,
+        })
+    );
+    let outcome = cache
+        .send(Ping { id: 2 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.status, CacheStatus::Hit);
+}
+
+#[actix::test]
+async fn test_write_behind_disabled() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend).start();
+    assert_eq!(cache.send(GetWriteBehindStats).await.unwrap(), None);
+}
+
+#[actix::test]
+async fn test_no_cache() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend.clone()).start();
+    let upstream = UpstreamActor.start();
+    let outcome = cache
+        .send(
+            Ping { id: 42 }
+                .into_cache(&upstream)
+                .no_cache()
+                .with_outcome(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.status, CacheStatus::Bypass);
+    assert_eq!(outcome.result.id, 42);
+    let messages = backend.send(GetMessages).await.unwrap().0;
+    assert!(messages.is_empty());
+}
+
+#[actix::test]
+async fn test_query_settings_override() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .disable()
+        .finish(backend.clone())
+        .start();
+    let upstream = UpstreamActor.start();
+    let settings = SettingsOverride::default().enable();
+    cache
+        .send(
+            Ping { id: 42 }
+                .into_cache(&upstream)
+                .with_settings(settings),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    let messages = backend.send(GetMessages).await.unwrap().0;
+    assert!(matches!(
+        messages[..],
+        [MockMessage::Get(_), MockMessage::Set(_)]
+    ));
+}
@@ -0,0 +1,94 @@
+use actix::prelude::*;
+use hitbox::dev::mock_backend::backend::{GetMessages, MockBackend, MockMessage};
+use hitbox_actix::prelude::*;
+use serde::Serialize;
+
+struct Upstream;
+
+impl Actor for Upstream {
+    type Context = Context<Self>;
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "i32")]
+struct Ping {
+    id: i32,
+}
+
+impl Handler<Ping> for Upstream {
+    type Result = i32;
+
+    fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+        msg.id
+    }
+}
+
+async fn stored_keys(backend: &Addr<MockBackend>) -> Vec<String> {
+    backend
+        .send(GetMessages)
+        .await
+        .unwrap()
+        .0
+        .into_iter()
+        .filter_map(|message| match message {
+            MockMessage::Set(set) => Some(set.key),
+            _ => None,
+        })
+        .collect()
+}
+
+#[actix::test]
+async fn test_namespace_key_prefix() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_namespace("svc:prod")
+        .finish(backend.clone())
+        .start();
+    let upstream = Upstream.start();
+    cache
+        .send(Ping { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        stored_keys(&backend).await,
+        vec!["svc:prod::Upstream::Ping::v0::id=42".to_owned()]
+    );
+}
+
+#[actix::test]
+async fn test_change_namespace_version() {
+    let backend = MockBackend::new().start();
+    let upstream = Upstream.start();
+    let cache = CacheActor::builder()
+        .with_namespace("svc:prod")
+        .with_namespace_version(3)
+        .finish(backend.clone())
+        .start();
+    for _ in 0..2 {
+        cache
+            .send(Ping { id: 42 }.into_cache(&upstream))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    let cache = CacheActor::builder()
+        .with_namespace("svc:prod")
+        .with_namespace_version(4)
+        .finish(backend.clone())
+        .start();
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.status, CacheStatus::Miss);
+    assert_eq!(
+        stored_keys(&backend).await,
+        vec![
+            "svc:prod::v3::Upstream::Ping::v0::id=42".to_owned(),
+            "svc:prod::v4::Upstream::Ping::v0::id=42".to_owned(),
+        ]
+    );
+}
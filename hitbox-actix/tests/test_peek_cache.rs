@@ -0,0 +1,132 @@
+use actix::prelude::*;
+use hitbox::dev::mock_backend::backend::MockBackend;
+use hitbox_actix::prelude::*;
+use serde::Serialize;
+
+#[derive(Default)]
+struct Upstream {
+    calls: u32,
+}
+
+impl Actor for Upstream {
+    type Context = Context<Self>;
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "i32")]
+struct Ping {
+    id: i32,
+}
+
+impl Handler<Ping> for Upstream {
+    type Result = i32;
+
+    fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+        self.calls += 1;
+        msg.id
+    }
+}
+
+#[derive(Message, Cacheable, Serialize)]
+#[rtype(result = "i32")]
+#[cache_stale_ttl(0)]
+struct StalePing {
+    id: i32,
+}
+
+impl Handler<StalePing> for Upstream {
+    type Result = i32;
+
+    fn handle(&mut self, msg: StalePing, _: &mut Self::Context) -> Self::Result {
+        self.calls += 1;
+        msg.id
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "u32")]
+struct GetCalls;
+
+impl Handler<GetCalls> for Upstream {
+    type Result = u32;
+
+    fn handle(&mut self, _: GetCalls, _: &mut Self::Context) -> Self::Result {
+        self.calls
+    }
+}
+
+#[actix::test]
+async fn test_peek_miss() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend).start();
+    let upstream = Upstream::default().start();
+    let state = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).peek())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(state, CacheState::Miss));
+    assert_eq!(upstream.send(GetCalls).await.unwrap(), 0);
+}
+
+#[actix::test]
+async fn test_peek_actual() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend).start();
+    let upstream = Upstream::default().start();
+    cache
+        .send(Ping { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    let state = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).peek())
+        .await
+        .unwrap()
+        .unwrap();
+    match state {
+        CacheState::Actual(value) => {
+            assert_eq!(*value.data(), 42);
+            assert!(value.expired() > chrono::Utc::now());
+        }
+        _ => panic!("Cached value should be actual"),
+    }
+    assert_eq!(upstream.send(GetCalls).await.unwrap(), 1);
+}
+
+#[actix::test]
+async fn test_peek_stale() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend).start();
+    let upstream = Upstream::default().start();
+    cache
+        .send(StalePing { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    let state = cache
+        .send(StalePing { id: 42 }.into_cache(&upstream).peek())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(state, CacheState::Stale(_)));
+    assert_eq!(upstream.send(GetCalls).await.unwrap(), 1);
+}
+
+#[actix::test]
+async fn test_peek_cache_disabled() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().disable().finish(backend).start();
+    let upstream = Upstream::default().start();
+    cache
+        .send(Ping { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    let state = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).peek())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(state, CacheState::Miss));
+}
@@ -0,0 +1,149 @@
+use actix::prelude::*;
+use hitbox::dev::mock_backend::backend::{GetMessages, MockBackend, MockMessage};
+use hitbox::settings::Status;
+use hitbox::{CacheError, Cacheable};
+use hitbox::{CachePolicy, CacheableResponse};
+use hitbox_actix::prelude::*;
+use serde::{Deserialize, Serialize};
+
+struct UpstreamActor;
+
+impl Actor for UpstreamActor {
+    type Context = Context<Self>;
+}
+
+#[derive(MessageResponse, CacheableResponse, Deserialize, Serialize, Debug)]
+struct Pong {
+    id: i32,
+}
+
+#[derive(Message, Serialize)]
+#[rtype(result = "Pong")]
+struct Ping {
+    pub id: i32,
+}
+
+impl Cacheable for Ping {
+    fn cache_key(&self) -> Result<String, CacheError> {
+        Ok(format!("{}::{}", self.cache_key_prefix(), self.id))
+    }
+    fn cache_key_prefix(&self) -> String {
+        "Ping".to_owned()
+    }
+}
+
+impl Handler<Ping> for UpstreamActor {
+    type Result = <Ping as Message>::Result;
+
+    fn handle(&mut self, msg: Ping, _ctx: &mut Self::Context) -> Self::Result {
+        Pong { id: msg.id }
+    }
+}
+
+#[actix::test]
+async fn test_kill_switch() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend.clone()).start();
+    let upstream = UpstreamActor.start();
+
+    let settings = cache
+        .send(UpdateSettings(SettingsOverride::default().disable()))
+        .await
+        .unwrap();
+    assert!(matches!(settings.settings.cache, Status::Disabled));
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.status, CacheStatus::Bypass);
+    assert!(backend.send(GetMessages).await.unwrap().0.is_empty());
+
+    cache
+        .send(UpdateSettings(SettingsOverride::default().enable()))
+        .await
+        .unwrap();
+    let outcome = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.status, CacheStatus::Miss);
+}
+
+#[actix::test]
+async fn test_get_settings() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .without_stale()
+        .with_ttl_multiplier(2.0)
+        .with_ttl_multiplier(f64::NAN)
+        .finish(backend)
+        .start();
+    let upstream = UpstreamActor.start();
+    let settings = cache.send(GetSettings).await.unwrap();
+    assert!(matches!(settings.settings.cache, Status::Enabled));
+    assert!(matches!(settings.settings.stale, Status::Disabled));
+    assert!(matches!(settings.settings.lock, Status::Disabled));
+    assert_eq!(settings.ttl_multiplier, 2.0);
+
+    let settings = cache
+        .send(UpdateSettings(SettingsOverride::default()))
+        .await
+        .unwrap();
+    assert!(matches!(settings.settings.stale, Status::Disabled));
+    assert!(matches!(settings.settings.lock, Status::Disabled));
+    let pong = cache
+        .send(Ping { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(pong.id, 42);
+}
+
+#[actix::test]
+async fn test_ttl_multiplier() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder().finish(backend.clone()).start();
+    let upstream = UpstreamActor.start();
+
+    let settings = cache.send(SetTtlMultiplier(0.5)).await.unwrap();
+    assert_eq!(settings.ttl_multiplier, 0.5);
+    let settings = cache.send(SetTtlMultiplier(-1.0)).await.unwrap();
+    assert_eq!(settings.ttl_multiplier, 0.5);
+
+    cache
+        .send(Ping { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    let messages = backend.send(GetMessages).await.unwrap().0;
+    match &messages[..] {
+        [MockMessage::Get(_), MockMessage::Set(set)] => assert_eq!(set.ttl, Some(30)),
+        messages => panic!("Unexpected backend messages {:?}", messages),
+    }
+}
+
+#[actix::test]
+async fn test_ttl_multiplier_keeps_ttl() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_ttl_multiplier(0.0)
+        .finish(backend.clone())
+        .start();
+    let upstream = UpstreamActor.start();
+
+    let settings = cache.send(GetSettings).await.unwrap();
+    assert_eq!(settings.ttl_multiplier, 1.0);
+    cache.send(SetTtlMultiplier(0.001)).await.unwrap();
+    cache
+        .send(Ping { id: 42 }.into_cache(&upstream))
+        .await
+        .unwrap()
+        .unwrap();
+    let messages = backend.send(GetMessages).await.unwrap().0;
+    match &messages[..] {
+        [MockMessage::Get(_), MockMessage::Set(set)] => assert_eq!(set.ttl, Some(1)),
+        messages => panic!("Unexpected backend messages {:?}", messages),
+    }
+}
@@ -0,0 +1,146 @@
+use actix::prelude::*;
+use hitbox::dev::mock_backend::backend::MockBackend;
+use hitbox_actix::prelude::*;
+use serde::Serialize;
+use std::time::Duration;
+
+/// Upstream which answers slowly to the first `slow_calls` messages.
+struct SlowUpstream {
+    slow_calls: u32,
+    calls: u32,
+}
+
+impl Actor for SlowUpstream {
+    type Context = Context<Self>;
+}
+
+#[derive(Message, Cacheable, Serialize, Clone)]
+#[rtype(result = "i32")]
+struct Ping {
+    id: i32,
+}
+
+impl Handler<Ping> for SlowUpstream {
+    type Result = ResponseFuture<i32>;
+
+    fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+        self.calls += 1;
+        let delay = if self.calls <= self.slow_calls {
+            Duration::from_secs(10)
+        } else {
+            Duration::from_millis(0)
+        };
+        Box::pin(async move {
+            actix::clock::sleep(delay).await;
+            msg.id
+        })
+    }
+}
+
+/// Message which value becomes stale right after caching.
+#[derive(Message, Cacheable, Serialize, Clone)]
+#[rtype(result = "i32")]
+#[cache_stale_ttl(0)]
+struct StalePing {
+    id: i32,
+}
+
+/// Answers the first message in time and the following ones slowly.
+impl Handler<StalePing> for SlowUpstream {
+    type Result = ResponseFuture<i32>;
+
+    fn handle(&mut self, msg: StalePing, _: &mut Self::Context) -> Self::Result {
+        self.calls += 1;
+        let delay = if self.calls > 1 {
+            Duration::from_secs(10)
+        } else {
+            Duration::from_millis(0)
+        };
+        Box::pin(async move {
+            actix::clock::sleep(delay).await;
+            msg.id
+        })
+    }
+}
+
+#[actix::test]
+async fn test_upstream_timeout() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_upstream_timeout(Duration::from_millis(50))
+        .finish(backend)
+        .start();
+    let upstream = SlowUpstream {
+        slow_calls: 1,
+        calls: 0,
+    }
+    .start();
+    let res = cache.send(Ping { id: 42 }.into_cache(&upstream)).await;
+    assert!(matches!(res.unwrap(), Err(CacheError::UpstreamError(_))));
+}
+
+#[actix::test]
+async fn test_upstream_retry() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_upstream_timeout(Duration::from_millis(50))
+        .with_upstream_retries(2, Duration::from_millis(10))
+        .finish(backend)
+        .start();
+    let upstream = SlowUpstream {
+        slow_calls: 2,
+        calls: 0,
+    }
+    .start();
+    let res = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).retryable())
+        .await;
+    assert_eq!(res.unwrap().unwrap(), 42);
+}
+
+#[actix::test]
+async fn test_upstream_retries_exhausted() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_upstream_timeout(Duration::from_millis(50))
+        .with_upstream_retries(1, Duration::from_millis(10))
+        .finish(backend)
+        .start();
+    let upstream = SlowUpstream {
+        slow_calls: 2,
+        calls: 0,
+    }
+    .start();
+    let res = cache
+        .send(Ping { id: 42 }.into_cache(&upstream).retryable())
+        .await;
+    assert!(res.unwrap().is_err());
+}
+
+#[actix::test]
+async fn test_upstream_timeout_returns_stale() {
+    let backend = MockBackend::new().start();
+    let cache = CacheActor::builder()
+        .with_upstream_timeout(Duration::from_millis(50))
+        .finish(backend)
+        .start();
+    let upstream = SlowUpstream {
+        slow_calls: 0,
+        calls: 0,
+    }
+    .start();
+    let outcome = cache
+        .send(StalePing { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.status, CacheStatus::Miss);
+
+    let outcome = cache
+        .send(StalePing { id: 42 }.into_cache(&upstream).with_outcome())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::StaleIfError);
+}
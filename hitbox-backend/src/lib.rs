@@ -2,10 +2,14 @@
 //! Traits and struct messages for hitbox backend interaction.
 //!
 //! If you want implement your own backend, you in the right place.
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use actix::dev::ToEnvelope;
 use actix::prelude::*;
 use thiserror::Error;
 
+pub use bytes::Bytes;
+
 /// Define the behavior needed of an cache layer to work with cache backend.
 ///
 /// Ultimately the implementing type must be an Actix `Actor` and it must implement handlers for a
@@ -15,27 +19,39 @@ use thiserror::Error;
 /// * [Set]
 /// * [Lock]
 /// * [Delete]
+/// * [Ping]
+///
+/// Backends may also implement handlers of [Scan], [KeySizes], [Stats], [GetMetadata]
+/// and [Peek] messages to make the cache contents inspectable.
 ///
 /// [Get]: crate::Get
 /// [Set]: crate::Set
 /// [Delete]: crate::Delete
 /// [Lock]: crate::Lock
+/// [Ping]: crate::Ping
+/// [Scan]: crate::Scan
+/// [KeySizes]: crate::KeySizes
+/// [Stats]: crate::Stats
+/// [GetMetadata]: crate::GetMetadata
+/// [Peek]: crate::Peek
 pub trait Backend
 where
-    Self: Actor + Handler<Set> + Handler<Get> + Handler<Lock> + Handler<Delete>,
+    Self: Actor + Handler<Set> + Handler<Get> + Handler<Lock> + Handler<Delete> + Handler<Ping>,
 {
     /// Type of backend actor bound.
     type Actor: Actor<Context = <Self as Backend>::Context>
         + Handler<Set>
         + Handler<Get>
         + Handler<Lock>
-        + Handler<Delete>;
+        + Handler<Delete>
+        + Handler<Ping>;
     /// Type for backend Actor context.
     type Context: ActorContext
         + ToEnvelope<Self::Actor, Get>
         + ToEnvelope<Self::Actor, Set>
         + ToEnvelope<Self::Actor, Lock>
-        + ToEnvelope<Self::Actor, Delete>;
+        + ToEnvelope<Self::Actor, Delete>
+        + ToEnvelope<Self::Actor, Ping>;
 }
 
 /// Proxy Error describes general groups of errors in backend interaction process.
@@ -49,11 +65,30 @@ pub enum BackendError {
     /// Network interaction error.
     #[error(transparent)]
     ConnectionError(Box<dyn std::error::Error + Send>),
+    /// Cache key exceeds the key length limit configured on the backend.
+    #[error("Cache key length {length} exceeds the limit of {max_length} bytes")]
+    KeyLengthError {
+        /// Length of the rejected key in bytes.
+        length: usize,
+        /// Key length limit in bytes.
+        max_length: usize,
+    },
+}
+
+/// Checks the cache key against the key length limit of the backend.
+pub fn check_key_length(key: &str, max_length: Option<usize>) -> Result<(), BackendError> {
+    match max_length {
+        Some(max_length) if key.len() > max_length => Err(BackendError::KeyLengthError {
+            length: key.len(),
+            max_length,
+        }),
+        _ => Ok(()),
+    }
 }
 
 /// Actix message requests cache backend value by key.
 #[derive(Message, Debug, Clone, PartialEq)]
-#[rtype(result = "Result<Option<Vec<u8>>, BackendError>")]
+#[rtype(result = "Result<Option<Bytes>, BackendError>")]
 pub struct Get {
     /// Key of cache backend record.
     pub key: String,
@@ -66,9 +101,16 @@ pub struct Set {
     /// Key of cache backend record.
     pub key: String,
     /// Data for sorage by cache key.
-    pub value: Vec<u8>,
+    ///
+    /// Shared buffer, so the message can be cloned without copying the data.
+    pub value: Bytes,
     /// Optional value of time-to-live for cache record.
     pub ttl: Option<u32>,
+    /// Optional time in seconds after which the record becomes stale.
+    ///
+    /// Stale time is also kept inside the serialized value, backends may store it natively
+    /// for inspection of the records without deserialization.
+    pub stale_ttl: Option<u32>,
 }
 
 /// Status of deleting result.
@@ -106,3 +148,268 @@ pub enum LockStatus {
     /// Lock object already acquired (locked).
     Locked,
 }
+
+/// Actix message checks cache backend health.
+///
+/// Backend should respond with `Ok(())` only if it's able to serve requests.
+#[derive(Message, Debug, Clone, PartialEq)]
+#[rtype(result = "Result<(), BackendError>")]
+pub struct Ping;
+
+/// Actix message requests keys of cache backend records matching the pattern.
+///
+/// Pattern uses glob-style syntax of redis `SCAN` command: `*` matches any sequence
+/// of characters, `?` matches a single character and `\` escapes the next character.
+/// Keys are returned by pages, the next page is requested with the cursor of the previous one.
+/// Order of returned keys is unspecified.
+#[derive(Message, Debug, Clone, PartialEq)]
+#[rtype(result = "Result<ScanPage, BackendError>")]
+pub struct Scan {
+    /// Glob-style pattern of cache keys, see [matches_pattern].
+    pub pattern: String,
+    /// Cursor returned with the previous page, `None` starts from the beginning.
+    pub cursor: Option<String>,
+    /// Maximum number of keys in the page, `None` returns all remaining keys.
+    ///
+    /// Backends iterating keys in batches, like redis, may return a few more keys.
+    pub limit: Option<usize>,
+}
+
+impl Scan {
+    /// Returns the page of keys stored in memory.
+    ///
+    /// Matching keys are sorted and the cursor is the last key of the page,
+    /// so records stored or removed between pages don't shift the iteration.
+    pub fn page<'a>(&self, keys: impl Iterator<Item = &'a String>) -> ScanPage {
+        let mut keys = keys
+            .filter(|key| matches_pattern(&self.pattern, key))
+            .filter(|key| match &self.cursor {
+                Some(cursor) => key.as_str() > cursor.as_str(),
+                None => true,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        let cursor = match self.limit {
+            Some(limit) if keys.len() > limit.max(1) => {
+                let limit = limit.max(1);
+                keys.truncate(limit);
+                keys.last().cloned()
+            }
+            _ => None,
+        };
+        ScanPage { keys, cursor }
+    }
+}
+
+/// Page of keys returned by [Scan] message.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScanPage {
+    /// Keys matching the pattern.
+    ///
+    /// Backends iterating keys in batches, like redis, may return a key in several pages.
+    pub keys: Vec<String>,
+    /// Cursor of the next page, `None` if there are no more keys.
+    pub cursor: Option<String>,
+}
+
+/// Actix message requests memory used by the records with the keys.
+///
+/// Returns sizes in bytes in the order of the keys, `None` for missing records.
+/// Sizes are estimated the same way as `bytes` of [Stats].
+#[derive(Message, Debug, Clone, PartialEq)]
+#[rtype(result = "Result<Vec<Option<u64>>, BackendError>")]
+pub struct KeySizes {
+    /// Keys of cache backend records.
+    pub keys: Vec<String>,
+}
+
+/// Actix message requests usage statistics of cache backend.
+#[derive(Message, Debug, Clone, PartialEq)]
+#[rtype(result = "Result<BackendStats, BackendError>")]
+pub struct Stats {
+    /// Count only records with keys matching the glob-style pattern, see [Scan].
+    ///
+    /// Statistics of the whole backend are returned if the pattern is missing.
+    pub pattern: Option<String>,
+}
+
+/// Usage statistics of cache backend.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BackendStats {
+    /// Number of stored records.
+    pub entries: u64,
+    /// Memory used by stored records in bytes.
+    ///
+    /// Backends may include their own overhead, so it's an estimate.
+    pub bytes: u64,
+    /// Number of records evicted because of the backend memory limits.
+    ///
+    /// Always counted for the whole backend, `None` if the backend doesn't evict records.
+    pub evictions: Option<u64>,
+}
+
+/// Actix message requests expiration metadata of the record without reading its value.
+///
+/// Returns `None` if the record is missing. Reading metadata doesn't change
+/// the record expiration.
+#[derive(Message, Debug, Clone, PartialEq)]
+#[rtype(result = "Result<Option<RecordMetadata>, BackendError>")]
+pub struct GetMetadata {
+    /// Key of cache backend record.
+    pub key: String,
+}
+
+/// Actix message reads cache backend value like [Get] without side effects.
+///
+/// Unlike [Get], reading the value doesn't extend the record expiration
+/// or change its eviction order, so the value can be inspected safely.
+#[derive(Message, Debug, Clone, PartialEq)]
+#[rtype(result = "Result<Option<Bytes>, BackendError>")]
+pub struct Peek {
+    /// Key of cache backend record.
+    pub key: String,
+}
+
+/// Expiration metadata of cache backend record.
+///
+/// Fields are `None` if the backend doesn't store them for the record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordMetadata {
+    /// Remaining time-to-live of the record, `None` if the record never expires.
+    pub ttl: Option<Duration>,
+    /// Record creation time in milliseconds since Unix epoch.
+    pub created_at: Option<u64>,
+    /// Time when the record becomes stale in milliseconds since Unix epoch,
+    /// see [Set::stale_ttl].
+    pub stale_at: Option<u64>,
+}
+
+impl RecordMetadata {
+    /// Returns `true` if the record is stale or `None` if its stale time is unknown.
+    pub fn is_stale(&self) -> Option<bool> {
+        self.stale_at.map(|stale_at| now_millis() >= stale_at)
+    }
+}
+
+/// Returns current time in milliseconds since Unix epoch, the time unit of [RecordMetadata].
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Checks the key against glob-style pattern of [Scan] message.
+pub fn matches_pattern(pattern: &str, key: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let key = key.chars().collect::<Vec<_>>();
+    let (mut p, mut k) = (0, 0);
+    // Position of the last `*` in the pattern and the key position it's matched with.
+    let mut backtrack = None;
+    while k < key.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, k));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                k += 1;
+                continue;
+            }
+            Some('\\') if pattern.get(p + 1) == Some(&key[k]) => {
+                p += 2;
+                k += 1;
+                continue;
+            }
+            Some(symbol) if *symbol != '\\' && *symbol == key[k] => {
+                p += 1;
+                k += 1;
+                continue;
+            }
+            _ => {}
+        }
+        match backtrack {
+            Some((star, matched)) => {
+                p = star + 1;
+                k = matched + 1;
+                backtrack = Some((star, matched + 1));
+            }
+            None => return false,
+        }
+    }
+    pattern[p..].iter().all(|symbol| *symbol == '*')
+}
+
+/// Escapes special characters, so the string is matched literally in [Scan] pattern.
+///
+/// Square brackets are escaped too, because redis treats them as character classes.
+pub fn escape_pattern(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for symbol in value.chars() {
+        if matches!(symbol, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(symbol);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*", ""));
+        assert!(matches_pattern("*", "Upstream::Message::v0::id=1"));
+        assert!(matches_pattern(
+            "Upstream::*",
+            "Upstream::Message::v0::id=1"
+        ));
+        assert!(matches_pattern("*::v?::*", "Upstream::Message::v0::id=1"));
+        assert!(matches_pattern("a*b*c", "aXbYbZc"));
+        assert!(!matches_pattern("Upstream::*", "lock::Upstream::Message"));
+        assert!(!matches_pattern("a?c", "ac"));
+        assert!(!matches_pattern("a*b", "aXbY"));
+    }
+
+    #[test]
+    fn test_escape_pattern() {
+        let key = "svc[1]::Message::v0::q=*?\\";
+        assert_eq!(escape_pattern("a*b"), "a\\*b");
+        assert!(matches_pattern(&escape_pattern(key), key));
+        assert!(matches_pattern(
+            &format!("{}*", escape_pattern("svc[1]")),
+            key
+        ));
+        assert!(!matches_pattern(&escape_pattern("a*"), "ab"));
+    }
+
+    #[test]
+    fn test_scan_page() {
+        let keys = ["b", "a", "c", "other"]
+            .iter()
+            .map(|key| key.to_string())
+            .collect::<Vec<_>>();
+        let mut scan = Scan {
+            pattern: "?".to_owned(),
+            cursor: None,
+            limit: Some(2),
+        };
+        let page = scan.page(keys.iter());
+        assert_eq!(page.keys, vec!["a", "b"]);
+        assert_eq!(page.cursor, Some("b".to_owned()));
+
+        scan.cursor = page.cursor;
+        let page = scan.page(keys.iter());
+        assert_eq!(page.keys, vec!["c"]);
+        assert_eq!(page.cursor, None);
+
+        scan.limit = None;
+        scan.cursor = None;
+        assert_eq!(scan.page(keys.iter()).keys.len(), 3);
+    }
+}
@@ -1,33 +1,85 @@
 use proc_macro::TokenStream;
 
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
 
-use crate::macro_attributes::find_attribute;
+use crate::macro_attributes::{
+    find_attribute, find_expr_attribute, find_ident_attribute, find_key_fields,
+    find_path_attribute, has_flag_attribute, parse_key_fields, KeyField, NumberAttribute,
+};
 
 /// Implementing Cacheable trait.
 ///
-/// Uses `serde_qs` crate to create a unique cache key.
-/// Default implementation of methods `cache_ttl`, `cache_stale_ttl` and `cache_version`
-/// are used if macros of the same name are not used.
+/// Uses `serde_qs` crate to create a unique cache key,
+/// another `KeyEncoder` can be selected by `cache_key_encoder` attribute.
+/// If some fields have `cache_key` attributes, each field is serialized separately
+/// and serde field attributes are ignored.
+/// Enum variants are always serialized field by field and get their own cache key prefixes.
+/// Default implementation of methods `cache_ttl`, `cache_stale_ttl`, `cache_version`
+/// and `cache_key_hasher` are used if macros of the same name are not used.
+/// `cache_settings` is implemented if `cache_enabled` or `cache_stale` is used,
+/// `cache_lock` is rejected until cache lock mechanics is implemented.
 pub fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
-    let name = &ast.ident;
-    let message_type = format!("{}", name);
+    match impl_cacheable(ast) {
+        Ok(gen) => gen.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
 
-    let cache_key_implement = quote! {
-        fn cache_key(&self) -> Result<String, CacheError> {
-            hitbox_serializer::to_string(self)
-                .map(|key| format!("{}::v{}::{}", self.cache_key_prefix(), self.cache_version(), key))
-                .map_err(|error| CacheError::CacheKeyGenerationError(error.to_string()))
-        }
+fn impl_cacheable(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let prefix_format = impl_cache_key_prefix_format(ast)?;
+    let encoder = match find_path_attribute(ast, "cache_key_encoder")? {
+        Some(encoder) => quote! { #encoder },
+        None => quote! { hitbox_serializer::QueryEncoder },
     };
 
-    let cache_key_prefix_implement = quote! {
-        fn cache_key_prefix(&self) -> String {
-            #message_type.to_owned()
+    let (cache_key_implement, cache_key_prefix_implement, bounds) = match &ast.data {
+        syn::Data::Enum(data) => impl_enum(data, prefix_format, &encoder)?,
+        _ => {
+            let cache_key_prefix_implement = quote! {
+                fn cache_key_prefix(&self) -> String {
+                    #prefix_format
+                }
+            };
+            match find_key_fields(ast)? {
+                Some(fields) => {
+                    let parts = fields.iter().filter(|field| !field.skip).map(|field| {
+                        let member = &field.member;
+                        key_part(field, quote! { &self.#member }, &encoder)
+                    });
+                    let cache_key_implement = impl_cache_key(quote! { vec![#(#parts),*] });
+                    (
+                        cache_key_implement,
+                        cache_key_prefix_implement,
+                        key_bounds(&fields, &encoder),
+                    )
+                }
+                None => {
+                    let cache_key_implement = quote! {
+                        fn cache_key(&self) -> Result<String, CacheError> {
+                            <#encoder as hitbox_serializer::KeyEncoder<Self>>::encode(self)
+                                .map(|key| match self.cache_key_hasher() {
+                                    Some(hasher) => hasher.hash(key.as_bytes()),
+                                    None => key,
+                                })
+                                .map(|key| format!("{}::v{}::{}", self.cache_key_prefix(), self.cache_version(), key))
+                        }
+                    };
+                    let bounds =
+                        vec![syn::parse_quote! { #encoder: hitbox_serializer::KeyEncoder<Self> }];
+                    (cache_key_implement, cache_key_prefix_implement, bounds)
+                }
+            }
         }
     };
 
-    let cache_ttl_implement = match find_attribute(ast, "cache_ttl") {
+    let cache_ttl = find_attribute(ast, "cache_ttl")?;
+    let cache_stale_ttl = find_attribute(ast, "cache_stale_ttl")?;
+    let cache_version = find_attribute(ast, "cache_version")?;
+    let cache_stale_ttl_check = impl_cache_stale_ttl_check(&cache_ttl, &cache_stale_ttl)?;
+
+    let cache_ttl_implement = match cache_ttl {
         Some(cache_ttl) => quote! {
             fn cache_ttl(&self) -> u32 {
                 #cache_ttl
@@ -36,7 +88,7 @@ pub fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
         None => proc_macro2::TokenStream::new(),
     };
 
-    let cache_stale_ttl_implement = match find_attribute(ast, "cache_stale_ttl") {
+    let cache_stale_ttl_implement = match cache_stale_ttl {
         Some(cache_stale_ttl) => quote! {
             fn cache_stale_ttl(&self) -> u32 {
                 #cache_stale_ttl
@@ -45,7 +97,16 @@ pub fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
         None => proc_macro2::TokenStream::new(),
     };
 
-    let cache_version_implement = match find_attribute(ast, "cache_version") {
+    let cache_key_hasher_implement = match find_ident_attribute(ast, "cache_key_hasher")? {
+        Some(hasher) => quote! {
+            fn cache_key_hasher(&self) -> Option<KeyHasher> {
+                Some(KeyHasher::#hasher)
+            }
+        },
+        None => proc_macro2::TokenStream::new(),
+    };
+
+    let cache_version_implement = match cache_version {
         Some(cache_version) => quote! {
             fn cache_version(&self) -> u32 {
                 #cache_version
@@ -54,14 +115,259 @@ pub fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
         None => proc_macro2::TokenStream::new(),
     };
 
-    let gen = quote! {
-        impl Cacheable for #name {
+    if let Some(lock) = find_expr_attribute(ast, "cache_lock")? {
+        return Err(syn::Error::new_spanned(
+            lock,
+            "cache_lock is not supported, cache lock mechanics is not implemented yet",
+        ));
+    }
+
+    let overrides = [("cache", "cache_enabled"), ("stale", "cache_stale")]
+        .iter()
+        .filter_map(|(field, attribute)| {
+            find_expr_attribute(ast, attribute)
+                .map(|expr| {
+                    expr.map(|expr| {
+                        let field = format_ident!("{}", field);
+                        quote! { #field: Some(::std::convert::From::from(#expr)) }
+                    })
+                })
+                .transpose()
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+    let cache_settings_implement = if overrides.is_empty() {
+        proc_macro2::TokenStream::new()
+    } else {
+        quote! {
+            #[allow(clippy::needless_update)]
+            fn cache_settings(&self) -> hitbox_serializer::SettingsOverride {
+                hitbox_serializer::SettingsOverride {
+                    #(#overrides,)*
+                    ..::std::default::Default::default()
+                }
+            }
+        }
+    };
+
+    // Encoder bounds are required only if encoded types depend on type parameters.
+    let mut generics = ast.generics.clone();
+    if generics.type_params().next().is_some() {
+        let type_names = generics
+            .type_params()
+            .map(|param| {
+                let ident = &param.ident;
+                syn::parse_quote! { #ident: hitbox_serializer::KeyTypeName }
+            })
+            .collect::<Vec<syn::WherePredicate>>();
+        let where_clause = generics.make_where_clause();
+        where_clause.predicates.extend(bounds);
+        where_clause.predicates.extend(type_names);
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics Cacheable for #name #ty_generics #where_clause {
             #cache_key_implement
             #cache_key_prefix_implement
             #cache_ttl_implement
             #cache_stale_ttl_implement
             #cache_version_implement
+            #cache_key_hasher_implement
+            #cache_settings_implement
         }
+
+        #cache_stale_ttl_check
+    })
+}
+
+/// Checks that `cache_stale_ttl` doesn't exceed `cache_ttl`.
+///
+/// Literals are compared by the macro itself, constant expressions and the default
+/// `cache_ttl` are compared by the generated constant evaluation.
+fn impl_cache_stale_ttl_check(
+    cache_ttl: &Option<NumberAttribute>,
+    cache_stale_ttl: &Option<NumberAttribute>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let cache_stale_ttl = match cache_stale_ttl {
+        Some(cache_stale_ttl) => cache_stale_ttl,
+        None => return Ok(proc_macro2::TokenStream::new()),
+    };
+    let (cache_ttl_value, cache_ttl) = match cache_ttl {
+        Some(cache_ttl) => (cache_ttl.value, quote! { #cache_ttl }),
+        None => (None, quote! { hitbox_serializer::DEFAULT_CACHE_TTL }),
     };
-    gen.into()
+    match (cache_stale_ttl.value, cache_ttl_value) {
+        (Some(stale_ttl), Some(ttl)) if stale_ttl > ttl => Err(syn::Error::new_spanned(
+            &cache_stale_ttl.expr,
+            format!(
+                "cache_stale_ttl ({}) should not exceed cache_ttl ({})",
+                stale_ttl, ttl
+            ),
+        )),
+        (Some(_), Some(_)) => Ok(proc_macro2::TokenStream::new()),
+        _ => Ok(quote_spanned! { cache_stale_ttl.expr.span() =>
+            #[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
+            const _: () = {
+                // Compilation fails with "index out of bounds" if cache_stale_ttl exceeds cache_ttl.
+                const CACHE_STALE_TTL_EXCEEDS_CACHE_TTL: bool = (#cache_stale_ttl) > (#cache_ttl);
+                [()][CACHE_STALE_TTL_EXCEEDS_CACHE_TTL as usize]
+            };
+        }),
+    }
+}
+
+/// Returns expression which formats the cache key prefix.
+///
+/// Prefix consists of the namespace, the module path and the custom prefix or the type name.
+/// Type and const parameters of generic types are appended to the last segment,
+/// so `Query<i32>` and `Query<String>` have different prefixes.
+/// Type parameters are named by `KeyTypeName` implementations.
+fn impl_cache_key_prefix_format(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut segments = Vec::new();
+    if let Some(namespace) = find_expr_attribute(ast, "cache_namespace")? {
+        segments.push(quote! { #namespace });
+    }
+    if has_flag_attribute(ast, "cache_module_path")? {
+        segments.push(quote! { module_path!() });
+    }
+    let name = match find_expr_attribute(ast, "cache_prefix")? {
+        Some(prefix) => quote! { #prefix },
+        None => {
+            let message_type = format!("{}", ast.ident);
+            quote! { #message_type }
+        }
+    };
+    let params = ast
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            syn::GenericParam::Type(param) => {
+                let ident = &param.ident;
+                Some(quote! { <#ident as hitbox_serializer::KeyTypeName>::key_type_name() })
+            }
+            syn::GenericParam::Const(param) => {
+                let ident = &param.ident;
+                Some(quote! { #ident.to_string() })
+            }
+            syn::GenericParam::Lifetime(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if params.is_empty() {
+        segments.push(name);
+    } else {
+        segments.push(quote! { format!("{}<{}>", #name, vec![#(#params),*].join(", ")) });
+    }
+    let format = vec!["{}"; segments.len()].join("::");
+    Ok(quote! { format!(#format, #(#segments),*) })
+}
+
+/// Implementing `cache_key` and `cache_key_prefix` methods for enum.
+///
+/// The variant name is appended to the cache key prefix
+/// and variant fields are serialized according to their `cache_key` attributes.
+fn impl_enum(
+    data: &syn::DataEnum,
+    prefix_format: proc_macro2::TokenStream,
+    encoder: &proc_macro2::TokenStream,
+) -> syn::Result<(
+    proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
+    Vec<syn::WherePredicate>,
+)> {
+    let mut bounds = Vec::new();
+    let mut key_arms = Vec::new();
+    let mut prefix_arms = Vec::new();
+    for variant in data.variants.iter() {
+        let ident = &variant.ident;
+        let variant_name = ident.to_string();
+        let fields = parse_key_fields(&variant.fields)?;
+        let members = fields.iter().map(|field| &field.member);
+        let bindings = fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                if field.skip {
+                    quote! { _ }
+                } else {
+                    let binding = format_ident!("__field{}", index);
+                    quote! { #binding }
+                }
+            })
+            .collect::<Vec<_>>();
+        let parts = fields
+            .iter()
+            .zip(bindings.iter())
+            .filter(|(field, _)| !field.skip)
+            .map(|(field, binding)| key_part(field, binding.clone(), encoder));
+        key_arms.push(quote! {
+            Self::#ident { #(#members: #bindings),* } => vec![#(#parts),*],
+        });
+        prefix_arms.push(quote! {
+            Self::#ident { .. } => #variant_name,
+        });
+        bounds.extend(key_bounds(&fields, encoder));
+    }
+    let cache_key_implement = impl_cache_key(quote! {
+        match self {
+            #(#key_arms)*
+        }
+    });
+    let cache_key_prefix_implement = quote! {
+        fn cache_key_prefix(&self) -> String {
+            let variant: &str = match self {
+                #(#prefix_arms)*
+            };
+            format!("{}::{}", #prefix_format, variant)
+        }
+    };
+    Ok((cache_key_implement, cache_key_prefix_implement, bounds))
+}
+
+/// Returns `KeyEncoder` bounds for types of fields which are encoded as is.
+fn key_bounds(fields: &[KeyField], encoder: &proc_macro2::TokenStream) -> Vec<syn::WherePredicate> {
+    fields
+        .iter()
+        .filter(|field| !field.skip && field.with.is_none())
+        .map(|field| {
+            let ty = &field.ty;
+            syn::parse_quote! { #encoder: hitbox_serializer::KeyEncoder<#ty> }
+        })
+        .collect()
+}
+
+/// Returns expression which encodes the field value into a cache key part.
+fn key_part(
+    field: &KeyField,
+    value: proc_macro2::TokenStream,
+    encoder: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let name = &field.name;
+    let value = match &field.with {
+        Some(with) => quote! { &#with(#value) },
+        None => value,
+    };
+    quote! {
+        <#encoder as hitbox_serializer::KeyEncoder<_>>::encode_field(#name, #value)
+    }
+}
+
+/// Implementing `cache_key` method which joins serialized fields.
+fn impl_cache_key(parts: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        fn cache_key(&self) -> Result<String, CacheError> {
+            let parts: Vec<Result<String, CacheError>> = #parts;
+            let parts = parts.into_iter().collect::<Result<Vec<String>, _>>()?;
+            let key = parts
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<String>>()
+                .join("&");
+            let key = match self.cache_key_hasher() {
+                Some(hasher) => hasher.hash(key.as_bytes()),
+                None => key,
+            };
+            Ok(format!("{}::v{}::{}", self.cache_key_prefix(), self.cache_version(), key))
+        }
+    }
 }
@@ -2,23 +2,258 @@ use proc_macro::TokenStream;
 
 use quote::quote;
 
+use crate::macro_attributes::{find_attribute, find_variant_policies, VariantPolicy};
+
 /// Implementing CacheableResponse trait.
+///
+/// Structs and enums without `cacheable`/`non_cacheable` variant attributes are always cacheable.
+/// If an enum with variant attributes has exactly one cacheable variant
+/// with a single field or without fields, the type of this field (or `()`) is used as
+/// `Cached` type. Otherwise `{Name}Cached` enum with the cacheable variants is generated
+/// and used as `Cached` type. Types of non cacheable variants are never stored.
+/// Default implementation of `cache_schema_version` is used if `cache_schema_version` macro
+/// is not used.
 pub fn impl_macro(ast: &syn::DeriveInput) -> TokenStream {
+    match impl_cacheable_response(ast) {
+        Ok(gen) => gen.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn impl_cacheable_response(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &ast.ident;
+    let policies = match &ast.data {
+        syn::Data::Enum(data) => find_variant_policies(data)?,
+        _ => None,
+    };
+
+    let CachedImpl {
+        cached,
+        serialized,
+        implement,
+        cached_enum,
+    } = match policies {
+        Some(policies) => impl_variant_policies(ast, &policies)?,
+        None => CachedImpl {
+            cached: quote! { Self },
+            serialized: vec![quote! { Self }],
+            implement: quote! {
+                fn cache_policy(&self) -> CachePolicy<hitbox_serializer::CachedRef<'_>, ()> {
+                    CachePolicy::Cacheable(hitbox_serializer::CachedRef::new(self))
+                }
+                fn into_cache_policy(self) -> CachePolicy<Self::Cached, Self> {
+                    CachePolicy::Cacheable(self)
+                }
+                fn from_cached(cached: Self::Cached) -> Self {
+                    cached
+                }
+            },
+            cached_enum: proc_macro2::TokenStream::new(),
+        },
+    };
+
+    let cache_schema_version_implement = match find_attribute(ast, "cache_schema_version")? {
+        Some(cache_schema_version) => quote! {
+            fn cache_schema_version() -> u32 {
+                #cache_schema_version
+            }
+        },
+        None => proc_macro2::TokenStream::new(),
+    };
+
+    // Cached data must be serializable, it depends on type parameters of generic types.
+    let mut generics = ast.generics.clone();
+    if generics.type_params().next().is_some() {
+        let where_clause = generics.make_where_clause();
+        for ty in serialized {
+            where_clause
+                .predicates
+                .push(syn::parse_quote! { #ty: hitbox_serializer::Serialize });
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #cached_enum
+
+        impl #impl_generics CacheableResponse for #name #ty_generics #where_clause {
+            type Cached = #cached;
+            #implement
+            #cache_schema_version_implement
+        }
+    })
+}
+
+/// Generated parts of `CacheableResponse` implementation.
+struct CachedImpl {
+    /// `Cached` type.
+    cached: proc_macro2::TokenStream,
+    /// Types which should be serializable, borrowed data is serialized by these types.
+    serialized: Vec<proc_macro2::TokenStream>,
+    /// Implementation of `CacheableResponse` methods.
+    implement: proc_macro2::TokenStream,
+    /// Generated `Cached` enum, if it's needed.
+    cached_enum: proc_macro2::TokenStream,
+}
+
+/// Returns `CacheableResponse` implementation parts for enum with variant attributes.
+fn impl_variant_policies(
+    ast: &syn::DeriveInput,
+    policies: &[VariantPolicy],
+) -> syn::Result<CachedImpl> {
+    let cacheable = policies
+        .iter()
+        .filter(|policy| policy.cacheable)
+        .collect::<Vec<_>>();
+    match cacheable.as_slice() {
+        [] => Err(syn::Error::new_spanned(
+            &ast.ident,
+            "enum should have a cacheable variant",
+        )),
+        [policy] if policy.fields.len() <= 1 => Ok(impl_variant_policy(policy)),
+        _ => Ok(impl_cached_enum(ast, &cacheable)),
+    }
+}
+
+/// Returns `CacheableResponse` implementation parts for the single cacheable variant
+/// with a single field or without fields.
+fn impl_variant_policy(policy: &VariantPolicy) -> CachedImpl {
+    let ident = &policy.ident;
+    let (cached, pattern, borrowed, owned, constructor) = match policy.fields.iter().next() {
+        None => (
+            quote! { () },
+            quote! { Self::#ident { .. } },
+            quote! { () },
+            quote! { () },
+            quote! { Self::#ident {} },
+        ),
+        Some(field) => {
+            let member = match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(0.into()),
+            };
+            let ty = &field.ty;
+            (
+                quote! { #ty },
+                quote! { Self::#ident { #member: value } },
+                quote! { value },
+                quote! { value },
+                quote! { Self::#ident { #member: cached } },
+            )
+        }
+    };
+    let implement = quote! {
+        #[allow(unreachable_patterns)]
+        fn cache_policy(&self) -> CachePolicy<hitbox_serializer::CachedRef<'_>, ()> {
+            match self {
+                #pattern => CachePolicy::Cacheable(hitbox_serializer::CachedRef::new(#borrowed)),
+                _ => CachePolicy::NonCacheable(()),
+            }
+        }
+        #[allow(unreachable_patterns)]
+        fn into_cache_policy(self) -> CachePolicy<Self::Cached, Self> {
+            match self {
+                #pattern => CachePolicy::Cacheable(#owned),
+                _ => CachePolicy::NonCacheable(self),
+            }
+        }
+        #[allow(unused_variables)]
+        fn from_cached(cached: Self::Cached) -> Self {
+            #constructor
+        }
+    };
+    CachedImpl {
+        serialized: vec![cached.clone()],
+        cached,
+        implement,
+        cached_enum: proc_macro2::TokenStream::new(),
+    }
+}
+
+/// Returns `CacheableResponse` implementation parts with `{Name}Cached` enum
+/// which mirrors cacheable variants.
+///
+/// Field types of the generated enum are type parameters, so it doesn't depend on generics
+/// of the original enum and the same enum of references serializes borrowed data.
+fn impl_cached_enum(ast: &syn::DeriveInput, cacheable: &[&VariantPolicy]) -> CachedImpl {
     let name = &ast.ident;
+    let vis = &ast.vis;
+    let cached_name = quote::format_ident!("{}Cached", name);
+    let doc = format!("Cacheable variants of [`{}`].", name);
 
-    let gen = quote! {
-        impl CacheableResponse for #name {
-            type Cached = #name;
-            fn cache_policy(&self) -> CachePolicy<&Self::Cached, ()> {
-                CachePolicy::Cacheable(self)
+    let mut types = Vec::new();
+    let mut params = Vec::new();
+    let mut variants = Vec::new();
+    let mut patterns = Vec::new();
+    let mut constructors = Vec::new();
+    for policy in cacheable {
+        let ident = &policy.ident;
+        let mut members = Vec::new();
+        let mut bindings = Vec::new();
+        let mut fields = Vec::new();
+        for (index, field) in policy.fields.iter().enumerate() {
+            let param = quote::format_ident!("T{}", types.len());
+            let binding = quote::format_ident!("value{}", index);
+            match &field.ident {
+                Some(field_ident) => {
+                    members.push(syn::Member::Named(field_ident.clone()));
+                    fields.push(quote! { #field_ident: #param });
+                }
+                None => {
+                    members.push(syn::Member::Unnamed(index.into()));
+                    fields.push(quote! { #param });
+                }
             }
-            fn into_cache_policy(self) -> CachePolicy<Self::Cached, Self> {
-                CachePolicy::Cacheable(self)
+            bindings.push(binding);
+            types.push(&field.ty);
+            params.push(param);
+        }
+        variants.push(match &policy.fields {
+            syn::Fields::Named(_) => quote! { #ident { #(#fields),* } },
+            syn::Fields::Unnamed(_) => quote! { #ident ( #(#fields),* ) },
+            syn::Fields::Unit => quote! { #ident },
+        });
+        patterns.push(quote! { Self::#ident { #(#members: #bindings),* } });
+        constructors.push(quote! { #ident { #(#members: #bindings),* } });
+    }
+
+    let cached_enum = quote! {
+        #[doc = #doc]
+        #[derive(hitbox_serializer::Serialize, hitbox_serializer::Deserialize)]
+        #[serde(crate = "hitbox_serializer::serde")]
+        #vis enum #cached_name<#(#params),*> {
+            #(#variants),*
+        }
+    };
+    let cached = quote! { #cached_name<#(#types),*> };
+    let borrowed = quote! { #cached_name::<#(&#types),*> };
+    let implement = quote! {
+        #[allow(unreachable_patterns)]
+        fn cache_policy(&self) -> CachePolicy<hitbox_serializer::CachedRef<'_>, ()> {
+            match self {
+                #(
+                    #patterns => CachePolicy::Cacheable(hitbox_serializer::CachedRef::new(#borrowed::#constructors)),
+                )*
+                _ => CachePolicy::NonCacheable(()),
+            }
+        }
+        #[allow(unreachable_patterns)]
+        fn into_cache_policy(self) -> CachePolicy<Self::Cached, Self> {
+            match self {
+                #(#patterns => CachePolicy::Cacheable(#cached_name::#constructors),)*
+                _ => CachePolicy::NonCacheable(self),
             }
-            fn from_cached(cached: Self::Cached) -> Self {
-                cached
+        }
+        fn from_cached(cached: Self::Cached) -> Self {
+            match cached {
+                #(#cached_name::#constructors => Self::#constructors,)*
             }
         }
     };
-    gen.into()
+    CachedImpl {
+        cached,
+        serialized: types.iter().map(|ty| quote! { #ty }).collect(),
+        implement,
+        cached_enum,
+    }
 }
@@ -18,6 +18,97 @@
 //! assert_eq!(message.cache_message_key().unwrap(), "Message::v100::field=42".to_string());
 //! ```
 //!
+//! `cache_ttl`, `cache_stale_ttl` and `cache_version` accept integer literals and constant
+//! expressions, like `#[cache_ttl(CONFIG_TTL)]`. Compilation fails if `cache_stale_ttl`
+//! exceeds `cache_ttl`.
+//!
+//! Long keys can be replaced with a hash of the serialized message by `cache_key_hasher`
+//! attribute, the prefix and the version stay readable:
+//! ```edition2018,ignore
+//! #[derive(Cacheable, Serialize)]
+//! #[cache_key_hasher(Sha256)]
+//! struct Message {
+//!     field: i32,
+//! };
+//! let message = Message { field: 42 };
+//! assert_eq!(
+//!     message.cache_key().unwrap(),
+//!     "Message::v0::7f7b819e4db943e95d1f31466f75dacb8ea1824394c364be1e55694380cc2727".to_string()
+//! );
+//! ```
+//!
+//! Types which aren't supported by `serde_qs`, like maps and nested enums, can use another
+//! `KeyEncoder`: `JsonEncoder` (canonical JSON), `HashEncoder` (`Hash` implementation)
+//! or a custom one:
+//! ```edition2018,ignore
+//! #[derive(Cacheable, Serialize)]
+//! #[cache_key_encoder(JsonEncoder)]
+//! struct Message {
+//!     filters: HashMap<String, i32>,
+//! };
+//! let message = Message { filters: vec![("b".to_owned(), 2), ("a".to_owned(), 1)].into_iter().collect() };
+//! assert_eq!(message.cache_key().unwrap(), r#"Message::v0::{"filters":{"a":1,"b":2}}"#.to_string());
+//! ```
+//!
+//! Fields can be excluded from the cache key, renamed or formatted by a custom function:
+//! ```edition2018,ignore
+//! fn lowercase(value: &str) -> String {
+//!     value.to_lowercase()
+//! }
+//!
+//! #[derive(Cacheable)]
+//! struct Message {
+//!     #[cache_key(rename = "q", with = lowercase)]
+//!     query: String,
+//!     #[cache_key(skip)]
+//!     request_id: String,
+//! };
+//! let message = Message { query: "Hello".to_owned(), request_id: "42".to_owned() };
+//! assert_eq!(message.cache_key().unwrap(), "Message::v0::q=hello".to_string());
+//! ```
+//!
+//! The cache key prefix is the type name by default.
+//! It can be replaced with `cache_prefix` attribute, extended with the module path
+//! by `cache_module_path` attribute and put into a namespace by `cache_namespace` attribute.
+//! Both `cache_prefix` and `cache_namespace` accept string literals and constants,
+//! so a module or crate level constant can be shared by many types:
+//! ```edition2018,ignore
+//! const NAMESPACE: &str = "billing";
+//!
+//! #[derive(Cacheable, Serialize)]
+//! #[cache_namespace(NAMESPACE)]
+//! #[cache_prefix("invoice")]
+//! struct GetInvoice {
+//!     id: i32,
+//! };
+//! let message = GetInvoice { id: 42 };
+//! assert_eq!(message.cache_key().unwrap(), "billing::invoice::v0::id=42".to_string());
+//! ```
+//!
+//! Cache actor settings can be overridden for the type by `cache_enabled` and `cache_stale`
+//! attributes, which accept boolean literals and constants. `cache_lock` attribute is reserved
+//! and produces a compile error until cache lock mechanics is implemented:
+//! ```edition2018,ignore
+//! #[derive(Cacheable, Serialize)]
+//! #[cache_stale(false)]
+//! struct GetBalance {
+//!     account: i32,
+//! };
+//! ```
+//!
+//! Generic types get their type parameters in the cache key prefix (`Query<i32>::v0::...`),
+//! type parameters should implement `KeyTypeName`, which gives them stable names.
+//! Enum variants are serialized field by field and get their own prefixes:
+//! ```edition2018,ignore
+//! #[derive(Cacheable)]
+//! enum Command {
+//!     Get { id: i32 },
+//!     Find(String),
+//! }
+//! assert_eq!(Command::Get { id: 42 }.cache_key().unwrap(), "Command::Get::v0::id=42".to_string());
+//! assert_eq!(Command::Find("a".to_owned()).cache_key().unwrap(), "Command::Find::v0::0=a".to_string());
+//! ```
+//!
 //! CacheableResponse example:
 //! ```edition2018,ignore
 //! use hitbox::response::CacheableResponse;
@@ -29,22 +120,66 @@
 //!     OptionTwo(String),
 //! }
 //! ```
+//!
+//! Enum variants can be marked as `cacheable` or `non_cacheable`.
+//! If some variants are marked as `cacheable`, other variants are not cached.
+//! Only the field of the cacheable variant is stored, so there should be exactly one
+//! cacheable variant with a single field or without fields:
+//! ```edition2018,ignore
+//! #[derive(CacheableResponse)]
+//! pub enum HttpResponse {
+//!     #[cacheable]
+//!     Ok(String),
+//!     Unauthorized(i32),
+//! }
+//! ```
+//!
+//! `cache_schema_version` attribute sets the schema version of the cached data.
+//! Data cached with another version is treated as missing:
+//! ```edition2018,ignore
+//! #[derive(CacheableResponse, Serialize, Deserialize)]
+//! #[cache_schema_version(2)]
+//! pub struct User {
+//!     id: i32,
+//!     name: String,
+//! }
+//! ```
 use proc_macro::TokenStream;
+use syn::parse_macro_input;
 
 mod cacheable_macro;
 mod cacheable_response_macro;
 mod macro_attributes;
 
 /// Derive Cacheable macro implementation.
-#[proc_macro_derive(Cacheable, attributes(cache_ttl, cache_stale_ttl, cache_version))]
+#[proc_macro_derive(
+    Cacheable,
+    attributes(
+        cache_ttl,
+        cache_stale_ttl,
+        cache_version,
+        cache_key,
+        cache_prefix,
+        cache_namespace,
+        cache_module_path,
+        cache_key_hasher,
+        cache_key_encoder,
+        cache_enabled,
+        cache_stale,
+        cache_lock
+    )
+)]
 pub fn cacheable_macro_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as syn::DeriveInput);
     cacheable_macro::impl_macro(&ast)
 }
 
 /// Derive CacheableResponse macro implementation.
-#[proc_macro_derive(CacheableResponse)]
+#[proc_macro_derive(
+    CacheableResponse,
+    attributes(cacheable, non_cacheable, cache_schema_version)
+)]
 pub fn cacheable_response_macro_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
+    let ast = parse_macro_input!(input as syn::DeriveInput);
     cacheable_response_macro::impl_macro(&ast)
 }
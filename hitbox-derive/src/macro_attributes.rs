@@ -1,25 +1,243 @@
-use syn::{Attribute, NestedMeta};
-
-fn parse_attribute(attr: &Attribute, method: &str) -> Option<u32> {
-    if attr.path.is_ident(method) {
-        let meta = &attr.parse_meta();
-        match meta {
-            Ok(syn::Meta::List(value)) => {
-                let nested = value.nested.first()?;
-                match nested {
-                    NestedMeta::Lit(syn::Lit::Int(value)) => Some(value.base10_parse().ok()?),
-                    _ => panic!("Parameter for macro {macro} should be u32", macro=method),
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Token};
+
+/// Value of `cache_ttl`, `cache_stale_ttl` or `cache_version` attribute.
+///
+/// It's either an integer literal or a constant expression, like `#[cache_ttl(CONFIG_TTL)]`.
+pub struct NumberAttribute {
+    /// Attribute parameter.
+    pub expr: syn::Expr,
+    /// Parameter value if it's an integer literal.
+    pub value: Option<u32>,
+}
+
+impl ToTokens for NumberAttribute {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        match self.value {
+            Some(value) => value.to_tokens(tokens),
+            None => self.expr.to_tokens(tokens),
+        }
+    }
+}
+
+fn parse_attribute(attr: &Attribute, name: &str) -> syn::Result<NumberAttribute> {
+    let expr: syn::Expr = attr.parse_args()?;
+    let value = match &expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(value),
+            ..
+        }) => Some(value.base10_parse()?),
+        syn::Expr::Lit(_) => {
+            return Err(syn::Error::new_spanned(
+                &expr,
+                format!(
+                    "{} parameter should be u32 literal or constant expression",
+                    name
+                ),
+            ))
+        }
+        _ => None,
+    };
+    Ok(NumberAttribute { expr, value })
+}
+
+/// Returns the value of `cache_ttl`, `cache_stale_ttl` or `cache_version` attribute.
+pub fn find_attribute(ast: &syn::DeriveInput, name: &str) -> syn::Result<Option<NumberAttribute>> {
+    ast.attrs
+        .iter()
+        .find(|attr| attr.path.is_ident(name))
+        .map(|attr| parse_attribute(attr, name))
+        .transpose()
+}
+
+/// Returns the expression passed to the attribute, like `#[cache_prefix("orders")]`.
+pub fn find_expr_attribute(ast: &syn::DeriveInput, name: &str) -> syn::Result<Option<syn::Expr>> {
+    ast.attrs
+        .iter()
+        .find(|attr| attr.path.is_ident(name))
+        .map(|attr| attr.parse_args())
+        .transpose()
+}
+
+/// Returns the identifier passed to the attribute, like `#[cache_key_hasher(Xxh3)]`.
+pub fn find_ident_attribute(ast: &syn::DeriveInput, name: &str) -> syn::Result<Option<syn::Ident>> {
+    ast.attrs
+        .iter()
+        .find(|attr| attr.path.is_ident(name))
+        .map(|attr| attr.parse_args())
+        .transpose()
+}
+
+/// Returns the path passed to the attribute, like `#[cache_key_encoder(JsonEncoder)]`.
+pub fn find_path_attribute(ast: &syn::DeriveInput, name: &str) -> syn::Result<Option<syn::Path>> {
+    ast.attrs
+        .iter()
+        .find(|attr| attr.path.is_ident(name))
+        .map(|attr| attr.parse_args())
+        .transpose()
+}
+
+/// Returns `true` if the flag attribute, like `#[cache_module_path]`, is present.
+pub fn has_flag_attribute(ast: &syn::DeriveInput, name: &str) -> syn::Result<bool> {
+    find_flag(&ast.attrs, name)
+}
+
+fn find_flag(attrs: &[Attribute], name: &str) -> syn::Result<bool> {
+    match attrs.iter().find(|attr| attr.path.is_ident(name)) {
+        Some(attr) if attr.tokens.is_empty() => Ok(true),
+        Some(attr) => Err(syn::Error::new_spanned(
+            attr,
+            format!("{} attribute doesn't take parameters", name),
+        )),
+        None => Ok(false),
+    }
+}
+
+/// Cache key settings of the field defined by `cache_key` attribute.
+pub struct KeyField {
+    /// Field name or index.
+    pub member: syn::Member,
+    /// Field type.
+    pub ty: syn::Type,
+    /// Field name in the cache key.
+    pub name: String,
+    /// Exclude field from the cache key.
+    pub skip: bool,
+    /// Function that converts the field into serializable value.
+    pub with: Option<syn::Path>,
+}
+
+enum KeyArgument {
+    Skip,
+    Rename(syn::LitStr),
+    With(syn::Path),
+}
+
+impl Parse for KeyArgument {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident: syn::Ident = input.parse()?;
+        match ident.to_string().as_str() {
+            "skip" => Ok(KeyArgument::Skip),
+            "rename" => {
+                input.parse::<Token![=]>()?;
+                Ok(KeyArgument::Rename(input.parse()?))
+            }
+            "with" => {
+                input.parse::<Token![=]>()?;
+                if input.peek(syn::LitStr) {
+                    let path: syn::LitStr = input.parse()?;
+                    Ok(KeyArgument::With(path.parse()?))
+                } else {
+                    Ok(KeyArgument::With(input.parse()?))
                 }
             }
-            _ => panic!("{macro} macro should have a parameter of type u32", macro=method),
+            _ => Err(syn::Error::new(
+                ident.span(),
+                "unknown cache_key parameter, expected `skip`, `rename` or `with`",
+            )),
         }
-    } else {
-        None
     }
 }
 
-pub fn find_attribute(ast: &syn::DeriveInput, method: &str) -> Option<u32> {
-    ast.attrs
+fn is_key_attribute(attr: &Attribute) -> bool {
+    attr.path.is_ident("cache_key")
+}
+
+fn parse_key_field(index: usize, field: &syn::Field) -> syn::Result<KeyField> {
+    let (member, name) = match &field.ident {
+        Some(ident) => (syn::Member::Named(ident.clone()), ident.unraw().to_string()),
+        None => (syn::Member::Unnamed(index.into()), index.to_string()),
+    };
+    let mut key_field = KeyField {
+        member,
+        ty: field.ty.clone(),
+        name,
+        skip: false,
+        with: None,
+    };
+    for attr in field.attrs.iter().filter(|attr| is_key_attribute(attr)) {
+        let arguments =
+            attr.parse_args_with(Punctuated::<KeyArgument, Token![,]>::parse_terminated)?;
+        for argument in arguments {
+            match argument {
+                KeyArgument::Skip => key_field.skip = true,
+                KeyArgument::Rename(name) => key_field.name = name.value(),
+                KeyArgument::With(path) => key_field.with = Some(path),
+            }
+        }
+    }
+    Ok(key_field)
+}
+
+/// Returns cache key settings for every field.
+pub fn parse_key_fields(fields: &syn::Fields) -> syn::Result<Vec<KeyField>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| parse_key_field(index, field))
+        .collect()
+}
+
+/// Returns cache key settings for every struct field
+/// or `None` if there are no `cache_key` attributes.
+pub fn find_key_fields(ast: &syn::DeriveInput) -> syn::Result<Option<Vec<KeyField>>> {
+    let fields = match &ast.data {
+        syn::Data::Struct(data) => &data.fields,
+        _ => return Ok(None),
+    };
+    let has_attributes = fields
+        .iter()
+        .any(|field| field.attrs.iter().any(is_key_attribute));
+    if !has_attributes {
+        return Ok(None);
+    }
+    parse_key_fields(fields).map(Some)
+}
+
+/// Cache policy of the enum variant defined by `cacheable` or `non_cacheable` attribute.
+pub struct VariantPolicy {
+    /// Variant identifier.
+    pub ident: syn::Ident,
+    /// Variant should be stored in the cache backend.
+    pub cacheable: bool,
+    /// Variant fields.
+    pub fields: syn::Fields,
+}
+
+/// Returns cache policy for every enum variant
+/// or `None` if there are no `cacheable` and `non_cacheable` attributes.
+///
+/// Variants without attributes are cacheable unless some variants are marked as `cacheable`.
+pub fn find_variant_policies(data: &syn::DataEnum) -> syn::Result<Option<Vec<VariantPolicy>>> {
+    let mut flags = Vec::new();
+    for variant in data.variants.iter() {
+        let cacheable = find_flag(&variant.attrs, "cacheable")?;
+        let non_cacheable = find_flag(&variant.attrs, "non_cacheable")?;
+        if cacheable && non_cacheable {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "variant can't be both cacheable and non_cacheable",
+            ));
+        }
+        flags.push((variant, cacheable, non_cacheable));
+    }
+    if flags
         .iter()
-        .find_map(|attr| parse_attribute(attr, method))
+        .all(|(_, cacheable, non_cacheable)| !cacheable && !non_cacheable)
+    {
+        return Ok(None);
+    }
+    let default = !flags.iter().any(|(_, cacheable, _)| *cacheable);
+    let policies = flags
+        .into_iter()
+        .map(|(variant, cacheable, non_cacheable)| VariantPolicy {
+            ident: variant.ident.clone(),
+            cacheable: cacheable || (default && !non_cacheable),
+            fields: variant.fields.clone(),
+        })
+        .collect();
+    Ok(Some(policies))
 }
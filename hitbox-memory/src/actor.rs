@@ -0,0 +1,442 @@
+//! In-memory backend actor implementation.
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use actix::prelude::*;
+use hitbox_backend::{
+    check_key_length, matches_pattern, now_millis, Backend, BackendStats, Bytes, Delete,
+    DeleteStatus, Get, GetMetadata, KeySizes, Lock, LockStatus, Peek, Ping, RecordMetadata, Scan,
+    Set, Stats,
+};
+use log::{debug, info};
+
+/// Stored cache record.
+struct Entry {
+    value: Bytes,
+    expires_at: Option<Instant>,
+    /// Creation time in milliseconds since Unix epoch.
+    created_at: u64,
+    /// Stale time in milliseconds since Unix epoch.
+    stale_at: Option<u64>,
+    /// Position of the record in the order of usage.
+    used_at: u64,
+}
+
+impl Entry {
+    fn is_expired(&self, now: Instant) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
+
+/// Default interval of expired records removal.
+const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Memory used by the record.
+fn entry_size(key: &str, value: &Bytes) -> usize {
+    key.len() + value.len()
+}
+
+/// In-memory cache backend.
+///
+/// This actor stores records in the process memory and provides them as storage [Backend]
+/// for hitbox. Expired records are removed on access and periodically, see
+/// [MemoryBackendBuilder::sweep_interval]. Records over the limits configured
+/// by [MemoryBackendBuilder] are evicted in least recently used order.
+///
+/// [Backend]: hitbox_backend::Backend
+pub struct MemoryBackend {
+    entries: HashMap<String, Entry>,
+    /// Keys of the records ordered by their last usage.
+    usage: BTreeMap<u64, String>,
+    locks: HashMap<String, Instant>,
+    clock: u64,
+    bytes: usize,
+    evictions: u64,
+    max_entries: Option<usize>,
+    max_bytes: Option<usize>,
+    max_key_length: Option<usize>,
+    sweep_interval: Duration,
+}
+
+impl MemoryBackend {
+    /// Create new backend instance without limits.
+    pub fn new() -> MemoryBackend {
+        Self::builder().build()
+    }
+
+    /// Creates new MemoryBackend builder with default settings.
+    pub fn builder() -> MemoryBackendBuilder {
+        MemoryBackendBuilder::default()
+    }
+
+    /// Returns the next position in the order of usage.
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, key: &str) -> Option<Entry> {
+        let entry = self.entries.remove(key)?;
+        self.usage.remove(&entry.used_at);
+        self.bytes -= entry_size(key, &entry.value);
+        Some(entry)
+    }
+
+    fn remove_expired(&mut self) {
+        let now = Instant::now();
+        let expired = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(now))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        for key in expired {
+            self.remove(&key);
+        }
+        self.locks.retain(|_, expires_at| *expires_at > now);
+    }
+
+    /// Evicts least recently used records until the limits are satisfied.
+    fn evict(&mut self) {
+        let over_limits = |backend: &Self| {
+            matches!(backend.max_entries, Some(max) if backend.entries.len() > max)
+                || matches!(backend.max_bytes, Some(max) if backend.bytes > max)
+        };
+        if over_limits(self) {
+            self.remove_expired();
+        }
+        while over_limits(self) {
+            let key = match self.usage.values().next() {
+                Some(key) => key.clone(),
+                None => break,
+            };
+            debug!("Memory backend evicts: {}", key);
+            self.remove(&key);
+            self.evictions += 1;
+        }
+    }
+}
+
+impl Default for MemoryBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Part of builder pattern implemetation for MemoryBackend actor.
+#[derive(Debug)]
+pub struct MemoryBackendBuilder {
+    max_entries: Option<usize>,
+    max_bytes: Option<usize>,
+    max_key_length: Option<usize>,
+    sweep_interval: Duration,
+}
+
+impl Default for MemoryBackendBuilder {
+    fn default() -> Self {
+        Self {
+            max_entries: None,
+            max_bytes: None,
+            max_key_length: None,
+            sweep_interval: DEFAULT_SWEEP_INTERVAL,
+        }
+    }
+}
+
+impl MemoryBackendBuilder {
+    /// Set the maximum number of stored records.
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Set the maximum size of stored keys and values in bytes.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Set the maximum cache key length in bytes.
+    ///
+    /// Messages with longer keys are rejected with [BackendError::KeyLengthError].
+    ///
+    /// [BackendError::KeyLengthError]: hitbox_backend::BackendError::KeyLengthError
+    pub fn max_key_length(mut self, max_key_length: usize) -> Self {
+        self.max_key_length = Some(max_key_length);
+        self
+    }
+
+    /// Set the interval of expired records and locks removal. (Default value is 60 seconds).
+    pub fn sweep_interval(mut self, interval: Duration) -> Self {
+        self.sweep_interval = interval;
+        self
+    }
+
+    /// Create new instance of MemoryBackend with passed settings.
+    pub fn build(self) -> MemoryBackend {
+        MemoryBackend {
+            entries: HashMap::new(),
+            usage: BTreeMap::new(),
+            locks: HashMap::new(),
+            clock: 0,
+            bytes: 0,
+            evictions: 0,
+            max_entries: self.max_entries,
+            max_bytes: self.max_bytes,
+            max_key_length: self.max_key_length,
+            sweep_interval: self.sweep_interval,
+        }
+    }
+}
+
+impl Backend for MemoryBackend {
+    type Actor = Self;
+    type Context = Context<Self>;
+}
+
+/// Implementation actix Actor trait for in-memory cache backend.
+impl Actor for MemoryBackend {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        info!("Cache actor started");
+        ctx.run_interval(self.sweep_interval, |backend, _| backend.remove_expired());
+    }
+}
+
+/// Implementation of Actix Handler for Get message.
+impl Handler<Get> for MemoryBackend {
+    type Result = <Get as Message>::Result;
+
+    fn handle(&mut self, msg: Get, _: &mut Self::Context) -> Self::Result {
+        check_key_length(&msg.key, self.max_key_length)?;
+        let expired = match self.entries.get(&msg.key) {
+            Some(entry) => entry.is_expired(Instant::now()),
+            None => return Ok(None),
+        };
+        if expired {
+            self.remove(&msg.key);
+            return Ok(None);
+        }
+        let tick = self.tick();
+        let usage = &mut self.usage;
+        Ok(self.entries.get_mut(&msg.key).map(|entry| {
+            usage.remove(&entry.used_at);
+            usage.insert(tick, msg.key.clone());
+            entry.used_at = tick;
+            entry.value.clone()
+        }))
+    }
+}
+
+/// Implementation of Actix Handler for Peek message.
+///
+/// Unlike [Get], the record keeps its position in the order of usage.
+impl Handler<Peek> for MemoryBackend {
+    type Result = <Peek as Message>::Result;
+
+    fn handle(&mut self, msg: Peek, _: &mut Self::Context) -> Self::Result {
+        check_key_length(&msg.key, self.max_key_length)?;
+        let now = Instant::now();
+        Ok(self
+            .entries
+            .get(&msg.key)
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| entry.value.clone()))
+    }
+}
+
+/// Implementation of Actix Handler for Set message.
+impl Handler<Set> for MemoryBackend {
+    type Result = <Set as Message>::Result;
+
+    fn handle(&mut self, msg: Set, _: &mut Self::Context) -> Self::Result {
+        check_key_length(&msg.key, self.max_key_length)?;
+        self.remove(&msg.key);
+        let used_at = self.tick();
+        let expires_at = msg
+            .ttl
+            .map(|ttl| Instant::now() + Duration::from_secs(u64::from(ttl)));
+        let created_at = now_millis();
+        let stale_at = msg
+            .stale_ttl
+            .map(|stale_ttl| created_at + u64::from(stale_ttl) * 1000);
+        self.bytes += entry_size(&msg.key, &msg.value);
+        self.usage.insert(used_at, msg.key.clone());
+        self.entries.insert(
+            msg.key,
+            Entry {
+                value: msg.value,
+                expires_at,
+                created_at,
+                stale_at,
+                used_at,
+            },
+        );
+        self.evict();
+        Ok("OK".to_owned())
+    }
+}
+
+/// Implementation of Actix Handler for Delete message.
+impl Handler<Delete> for MemoryBackend {
+    type Result = <Delete as Message>::Result;
+
+    fn handle(&mut self, msg: Delete, _: &mut Self::Context) -> Self::Result {
+        check_key_length(&msg.key, self.max_key_length)?;
+        let now = Instant::now();
+        match self.remove(&msg.key) {
+            Some(entry) if !entry.is_expired(now) => Ok(DeleteStatus::Deleted(1)),
+            _ => Ok(DeleteStatus::Missing),
+        }
+    }
+}
+
+/// Implementation of Actix Handler for Lock message.
+impl Handler<Lock> for MemoryBackend {
+    type Result = <Lock as Message>::Result;
+
+    fn handle(&mut self, msg: Lock, _: &mut Self::Context) -> Self::Result {
+        check_key_length(&msg.key, self.max_key_length)?;
+        let now = Instant::now();
+        match self.locks.get(&msg.key) {
+            Some(expires_at) if *expires_at > now => Ok(LockStatus::Locked),
+            _ => {
+                let expires_at = now + Duration::from_secs(u64::from(msg.ttl));
+                self.locks.insert(msg.key, expires_at);
+                Ok(LockStatus::Acquired)
+            }
+        }
+    }
+}
+
+/// Implementation of Actix Handler for Ping message.
+impl Handler<Ping> for MemoryBackend {
+    type Result = <Ping as Message>::Result;
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) -> Self::Result {
+        Ok(())
+    }
+}
+
+/// Implementation of Actix Handler for GetMetadata message.
+///
+/// Unlike [Get], it doesn't change the position of the record in the order of usage.
+impl Handler<GetMetadata> for MemoryBackend {
+    type Result = <GetMetadata as Message>::Result;
+
+    fn handle(&mut self, msg: GetMetadata, _: &mut Self::Context) -> Self::Result {
+        check_key_length(&msg.key, self.max_key_length)?;
+        let now = Instant::now();
+        Ok(self
+            .entries
+            .get(&msg.key)
+            .filter(|entry| !entry.is_expired(now))
+            .map(|entry| RecordMetadata {
+                ttl: entry.expires_at.map(|expires_at| expires_at - now),
+                created_at: Some(entry.created_at),
+                stale_at: entry.stale_at,
+            }))
+    }
+}
+
+/// Implementation of Actix Handler for Scan message.
+impl Handler<Scan> for MemoryBackend {
+    type Result = <Scan as Message>::Result;
+
+    fn handle(&mut self, msg: Scan, _: &mut Self::Context) -> Self::Result {
+        self.remove_expired();
+        Ok(msg.page(self.entries.keys()))
+    }
+}
+
+/// Implementation of Actix Handler for KeySizes message.
+///
+/// Sizes are the lengths of keys and values, like `bytes` of [Stats].
+impl Handler<KeySizes> for MemoryBackend {
+    type Result = <KeySizes as Message>::Result;
+
+    fn handle(&mut self, msg: KeySizes, _: &mut Self::Context) -> Self::Result {
+        let now = Instant::now();
+        Ok(msg
+            .keys
+            .iter()
+            .map(|key| {
+                self.entries
+                    .get(key)
+                    .filter(|entry| !entry.is_expired(now))
+                    .map(|entry| entry_size(key, &entry.value) as u64)
+            })
+            .collect())
+    }
+}
+
+/// Implementation of Actix Handler for Stats message.
+///
+/// `bytes` is the total length of stored keys and values.
+impl Handler<Stats> for MemoryBackend {
+    type Result = <Stats as Message>::Result;
+
+    fn handle(&mut self, msg: Stats, _: &mut Self::Context) -> Self::Result {
+        self.remove_expired();
+        let (entries, bytes) = match msg.pattern {
+            None => (self.entries.len(), self.bytes),
+            Some(pattern) => self
+                .entries
+                .iter()
+                .filter(|(key, _)| matches_pattern(&pattern, key))
+                .fold((0, 0), |(entries, bytes), (key, entry)| {
+                    (entries + 1, bytes + entry_size(key, &entry.value))
+                }),
+        };
+        Ok(BackendStats {
+            entries: entries as u64,
+            bytes: bytes as u64,
+            evictions: Some(self.evictions),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Requests the number of stored records and locks.
+    #[derive(Message)]
+    #[rtype(result = "(usize, usize)")]
+    struct CountStored;
+
+    impl Handler<CountStored> for MemoryBackend {
+        type Result = MessageResult<CountStored>;
+
+        fn handle(&mut self, _: CountStored, _: &mut Self::Context) -> Self::Result {
+            MessageResult((self.entries.len(), self.locks.len()))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_sweep_expired() {
+        let mut backend = MemoryBackend::builder()
+            .sweep_interval(Duration::from_millis(10))
+            .build();
+        let expired = Instant::now();
+        backend.entries.insert(
+            "expired".to_owned(),
+            Entry {
+                value: Bytes::from_static(b"value"),
+                expires_at: Some(expired),
+                created_at: 0,
+                stale_at: None,
+                used_at: 0,
+            },
+        );
+        backend.usage.insert(0, "expired".to_owned());
+        backend.bytes = entry_size("expired", &Bytes::from_static(b"value"));
+        backend.locks.insert("expired".to_owned(), expired);
+        let addr = backend.start();
+        assert_eq!(addr.send(CountStored).await.unwrap(), (1, 1));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(addr.send(CountStored).await.unwrap(), (0, 0));
+    }
+}
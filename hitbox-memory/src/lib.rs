@@ -0,0 +1,12 @@
+#![warn(missing_docs)]
+//! hitbox [Backend] implementation storing records in the process memory.
+//!
+//! Records are kept in a hash map owned by the backend actor, so they aren't shared
+//! between processes and are lost on restart. Limits of entries and bytes are enforced
+//! by eviction of the least recently used records.
+//!
+//! [Backend]: hitbox_backend::Backend
+pub mod actor;
+
+#[doc(inline)]
+pub use crate::actor::{MemoryBackend, MemoryBackendBuilder};
@@ -0,0 +1,219 @@
+use actix::prelude::*;
+use hitbox_backend::{
+    escape_pattern, BackendStats, Bytes, Delete, DeleteStatus, Get, GetMetadata, KeySizes, Lock,
+    LockStatus, Peek, Scan, Set, Stats,
+};
+use hitbox_memory::MemoryBackend;
+use tokio::time::{sleep, Duration};
+
+fn set(key: &str, value: &'static [u8], ttl: Option<u32>) -> Set {
+    Set {
+        key: key.to_owned(),
+        value: Bytes::from_static(value),
+        ttl,
+        stale_ttl: None,
+    }
+}
+
+#[actix_rt::test]
+async fn test_rw() {
+    let addr = MemoryBackend::new().start();
+    let message = set("key", b"value", None);
+    let res = addr.send(message.clone()).await.unwrap().unwrap();
+    assert_eq!(res, "OK");
+    let res = addr
+        .send(Get {
+            key: message.key.clone(),
+        })
+        .await;
+    assert_eq!(res.unwrap().unwrap(), Some(message.value));
+
+    let res = addr.send(Delete { key: message.key }).await.unwrap();
+    assert_eq!(res.unwrap(), DeleteStatus::Deleted(1));
+}
+
+#[actix_rt::test]
+async fn test_set_expired() {
+    let addr = MemoryBackend::new().start();
+    let message = set("key_expired", b"value", Some(1));
+    addr.send(message.clone()).await.unwrap().unwrap();
+
+    sleep(Duration::from_secs(1)).await;
+
+    let res = addr
+        .send(Get {
+            key: message.key.clone(),
+        })
+        .await;
+    assert_eq!(res.unwrap().unwrap(), None);
+    let res = addr.send(Delete { key: message.key }).await.unwrap();
+    assert_eq!(res.unwrap(), DeleteStatus::Missing);
+}
+
+#[actix_rt::test]
+async fn test_metadata() {
+    let addr = MemoryBackend::new().start();
+    let message = Set {
+        stale_ttl: Some(5),
+        ..set("metadata_key", b"value", Some(10))
+    };
+    addr.send(message.clone()).await.unwrap().unwrap();
+
+    let metadata = addr
+        .send(GetMetadata { key: message.key })
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(metadata.ttl.unwrap() <= Duration::from_secs(10));
+    let created_at = metadata.created_at.unwrap();
+    assert_eq!(metadata.stale_at, Some(created_at + 5000));
+    assert_eq!(metadata.is_stale(), Some(false));
+
+    let res = addr
+        .send(GetMetadata {
+            key: "missing".to_owned(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(res.is_none());
+}
+
+#[actix_rt::test]
+async fn test_lock() {
+    let addr = MemoryBackend::new().start();
+    let message = Lock {
+        key: "lock_key".to_owned(),
+        ttl: 1,
+    };
+    let res = addr.send(message.clone()).await.unwrap().unwrap();
+    assert_eq!(res, LockStatus::Acquired);
+
+    let res = addr.send(message.clone()).await.unwrap().unwrap();
+    assert_eq!(res, LockStatus::Locked);
+
+    sleep(Duration::from_secs(1)).await;
+
+    let res = addr.send(message.clone()).await.unwrap().unwrap();
+    assert_eq!(res, LockStatus::Acquired);
+}
+
+#[actix_rt::test]
+async fn test_lru_eviction() {
+    let addr = MemoryBackend::builder().max_entries(2).build().start();
+    addr.send(set("first", b"1", None)).await.unwrap().unwrap();
+    addr.send(set("second", b"2", None)).await.unwrap().unwrap();
+    addr.send(Get {
+        key: "first".to_owned(),
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    addr.send(set("third", b"3", None)).await.unwrap().unwrap();
+
+    let page = addr
+        .send(Scan {
+            pattern: "*".to_owned(),
+            cursor: None,
+            limit: None,
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.keys, vec!["first", "third"]);
+
+    let stats = addr.send(Stats { pattern: None }).await.unwrap().unwrap();
+    assert_eq!(
+        stats,
+        BackendStats {
+            entries: 2,
+            bytes: 12,
+            evictions: Some(1),
+        }
+    );
+}
+
+#[actix_rt::test]
+async fn test_peek_keeps_usage_order() {
+    let addr = MemoryBackend::builder().max_entries(2).build().start();
+    addr.send(set("first", b"1", None)).await.unwrap().unwrap();
+    addr.send(set("second", b"2", None)).await.unwrap().unwrap();
+    let value = addr
+        .send(Peek {
+            key: "first".to_owned(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(value, Some(Bytes::from_static(b"1")));
+    addr.send(set("third", b"3", None)).await.unwrap().unwrap();
+
+    let page = addr
+        .send(Scan {
+            pattern: "*".to_owned(),
+            cursor: None,
+            limit: None,
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.keys, vec!["second", "third"]);
+}
+
+#[actix_rt::test]
+async fn test_max_bytes() {
+    let addr = MemoryBackend::builder().max_bytes(10).build().start();
+    addr.send(set("a", b"12345", None)).await.unwrap().unwrap();
+    addr.send(set("b", b"12345", None)).await.unwrap().unwrap();
+
+    let stats = addr.send(Stats { pattern: None }).await.unwrap().unwrap();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.bytes, 6);
+    assert_eq!(stats.evictions, Some(1));
+}
+
+#[actix_rt::test]
+async fn test_scan_and_stats_by_pattern() {
+    let addr = MemoryBackend::new().start();
+    addr.send(set("Upstream::Ping::v0::id=1", b"pong", None))
+        .await
+        .unwrap()
+        .unwrap();
+    addr.send(set("Upstream::Ping::v0::id=2", b"pong", None))
+        .await
+        .unwrap()
+        .unwrap();
+    addr.send(set("Upstream::Query::v0::q=*", b"result", None))
+        .await
+        .unwrap()
+        .unwrap();
+
+    let pattern = format!("{}*", escape_pattern("Upstream::Ping::"));
+    let mut scan = Scan {
+        pattern,
+        cursor: None,
+        limit: Some(1),
+    };
+    let page = addr.send(scan.clone()).await.unwrap().unwrap();
+    assert_eq!(page.keys, vec!["Upstream::Ping::v0::id=1"]);
+    scan.cursor = page.cursor;
+    let page = addr.send(scan).await.unwrap().unwrap();
+    assert_eq!(page.keys, vec!["Upstream::Ping::v0::id=2"]);
+    assert_eq!(page.cursor, None);
+
+    let keys = vec!["Upstream::Query::v0::q=*".to_owned(), "missing".to_owned()];
+    let sizes = addr.send(KeySizes { keys }).await.unwrap().unwrap();
+    assert_eq!(sizes, vec![Some(30), None]);
+
+    let pattern = escape_pattern("Upstream::Query::v0::q=*");
+    let stats = addr
+        .send(Stats {
+            pattern: Some(pattern),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.entries, 1);
+    assert_eq!(stats.bytes, 30);
+}
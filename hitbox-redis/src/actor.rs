@@ -1,19 +1,57 @@
 //! Redis backend actor implementation.
 use crate::error::Error;
 use actix::prelude::*;
-use hitbox_backend::{Backend, BackendError, Delete, DeleteStatus, Get, Lock, LockStatus, Set};
+use hitbox_backend::{
+    check_key_length, now_millis, Backend, BackendError, BackendStats, Bytes, Delete, DeleteStatus,
+    Get, GetMetadata, KeySizes, Lock, LockStatus, Peek, Ping, RecordMetadata, Scan, ScanPage, Set,
+    Stats,
+};
 use log::{debug, info};
-use redis::{aio::ConnectionManager, Client};
+use redis::{ConnectionInfo, IntoConnectionInfo};
+use std::time::Duration;
+
+use crate::connection::RedisConnection;
+#[cfg(feature = "tls")]
+use crate::connection::TlsSettings;
+use serde::Deserialize;
+
+/// Hash field of the serialized value.
+const VALUE_FIELD: &str = "value";
+/// Hash field of the record creation time.
+const CREATED_AT_FIELD: &str = "created_at";
+/// Hash field of the record stale time.
+const STALE_AT_FIELD: &str = "stale_at";
+
+/// Layout of cache records in redis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedisStorage {
+    /// Serialized value is stored as a string. (Default value).
+    String,
+    /// Serialized value is stored in `value` field of a hash, `created_at` and `stale_at`
+    /// fields hold milliseconds since Unix epoch.
+    ///
+    /// Stale status can be checked with `redis-cli` or [GetMetadata] message
+    /// without deserialization of the value.
+    ///
+    /// [GetMetadata]: hitbox_backend::GetMetadata
+    Hash,
+}
 
 /// Redis cache backend based on redis-rs crate.
 ///
 /// This actor provides redis as storage [Backend] for hitbox.
-/// Its use one [MultiplexedConnection] for asynchronous network interaction.
+/// Its use one [MultiplexedConnection] for asynchronous network interaction,
+/// or a pool of them configured by [RedisBackendBuilder::pool_size].
 ///
 /// [MultiplexedConnection]: redis::aio::MultiplexedConnection
 /// [Backend]: hitbox_backend::Backend
 pub struct RedisBackend {
-    connection: ConnectionManager,
+    connections: Vec<RedisConnection>,
+    next_connection: usize,
+    max_key_length: Option<usize>,
+    storage: RedisStorage,
+    sliding_ttl: Option<Duration>,
 }
 
 impl RedisBackend {
@@ -36,33 +74,187 @@ impl RedisBackend {
     pub fn builder() -> RedisBackendBuilder {
         RedisBackendBuilder::default()
     }
+
+    /// Returns the next connection of the pool.
+    fn connection(&mut self) -> RedisConnection {
+        let connection = self.connections[self.next_connection].clone();
+        self.next_connection = (self.next_connection + 1) % self.connections.len();
+        connection
+    }
 }
 
 /// Part of builder pattern implemetation for RedisBackend actor.
 pub struct RedisBackendBuilder {
     connection_info: String,
+    max_key_length: Option<usize>,
+    username: Option<String>,
+    password: Option<String>,
+    database: Option<i64>,
+    connect_timeout: Option<Duration>,
+    response_timeout: Option<Duration>,
+    pool_size: usize,
+    storage: RedisStorage,
+    sliding_ttl: Option<Duration>,
+    #[cfg(feature = "tls")]
+    tls: TlsSettings,
 }
 
 impl Default for RedisBackendBuilder {
     fn default() -> Self {
         Self {
             connection_info: "redis://127.0.0.1/".to_owned(),
+            max_key_length: None,
+            username: None,
+            password: None,
+            database: None,
+            connect_timeout: None,
+            response_timeout: None,
+            pool_size: 1,
+            storage: RedisStorage::String,
+            sliding_ttl: None,
+            #[cfg(feature = "tls")]
+            tls: TlsSettings::default(),
         }
     }
 }
 
 impl RedisBackendBuilder {
     /// Set connection info (host, port, database, etc.) for RedisBackend actor.
+    ///
+    /// Use `rediss://` scheme for TLS connections (requires `tls` feature).
     pub fn server(mut self, connection_info: String) -> Self {
         self.connection_info = connection_info;
         self
     }
 
+    /// Set the username for ACL authentication, overrides the username from connection info.
+    pub fn username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    /// Set the password for authentication, overrides the password from connection info.
+    pub fn password(mut self, password: impl Into<String>) -> Self {
+        self.password = Some(password.into());
+        self
+    }
+
+    /// Set the database number, overrides the database from connection info.
+    pub fn database(mut self, database: i64) -> Self {
+        self.database = Some(database);
+        self
+    }
+
+    /// Limit the duration of establishing each connection, including authentication.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Limit the duration of each command response.
+    ///
+    /// Timed out commands fail with connection error.
+    pub fn response_timeout(mut self, timeout: Duration) -> Self {
+        self.response_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the number of multiplexed connections. (Default value is 1).
+    ///
+    /// Commands are distributed between connections in round-robin order.
+    pub fn pool_size(mut self, pool_size: usize) -> Self {
+        self.pool_size = pool_size.max(1);
+        self
+    }
+
+    /// Set the layout of cache records in redis.
+    pub fn storage(mut self, storage: RedisStorage) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    /// Reset time-to-live of the record to `ttl` on each read.
+    ///
+    /// Frequently requested records stay in the cache, while their stale time is unchanged.
+    /// The time-to-live is replaced regardless of `cache_ttl` of the message, so `ttl`
+    /// shorter than `cache_ttl` shortens the record lifetime on the first read.
+    /// Uses `GETEX` command with [RedisStorage::String], which requires Redis 6.2 or newer.
+    pub fn sliding_ttl(mut self, ttl: Duration) -> Self {
+        self.sliding_ttl = Some(ttl);
+        self
+    }
+
+    /// Add trusted root certificate in PEM format for TLS connections.
+    #[cfg(feature = "tls")]
+    pub fn tls_ca_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.tls.ca_certificates.push(pem.into());
+        self
+    }
+
+    /// Set client certificate and private key in PKCS #12 format for TLS connections.
+    #[cfg(feature = "tls")]
+    pub fn tls_client_identity(
+        mut self,
+        pkcs12: impl Into<Vec<u8>>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.tls.identity = Some((pkcs12.into(), password.into()));
+        self
+    }
+
+    /// Set the limit of cache key length in bytes.
+    ///
+    /// Requests with longer keys fail with [BackendError::KeyLengthError]
+    /// without sending them to redis.
+    ///
+    /// [BackendError::KeyLengthError]: hitbox_backend::BackendError::KeyLengthError
+    pub fn max_key_length(mut self, max_key_length: usize) -> Self {
+        self.max_key_length = Some(max_key_length);
+        self
+    }
+
     /// Create new instance of Redis backend with passed settings.
     pub async fn build(&self) -> Result<RedisBackend, Error> {
-        let client = Client::open(self.connection_info.as_str())?;
-        let connection = client.get_tokio_connection_manager().await?;
-        Ok(RedisBackend { connection })
+        let mut connection_info = self.connection_info.as_str().into_connection_info()?;
+        if let Some(username) = &self.username {
+            connection_info.redis.username = Some(username.clone());
+        }
+        if let Some(password) = &self.password {
+            connection_info.redis.password = Some(password.clone());
+        }
+        if let Some(database) = self.database {
+            connection_info.redis.db = database;
+        }
+        let mut connections = Vec::with_capacity(self.pool_size);
+        for _ in 0..self.pool_size {
+            connections.push(self.connect(&connection_info).await?);
+        }
+        Ok(RedisBackend {
+            connections,
+            next_connection: 0,
+            max_key_length: self.max_key_length,
+            storage: self.storage,
+            sliding_ttl: self.sliding_ttl,
+        })
+    }
+
+    async fn connect(&self, connection_info: &ConnectionInfo) -> Result<RedisConnection, Error> {
+        let connection = async {
+            #[cfg(feature = "tls")]
+            {
+                if self.tls.is_custom() {
+                    return RedisConnection::connect_tls(connection_info, &self.tls).await;
+                }
+            }
+            RedisConnection::connect(connection_info).await
+        };
+        let connection = match self.connect_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, connection)
+                .await
+                .map_err(|_| Error::ConnectTimeout)??,
+            None => connection.await?,
+        };
+        Ok(connection.with_response_timeout(self.response_timeout))
     }
 }
 
@@ -82,15 +274,83 @@ impl Actor for RedisBackend {
 
 /// Implementation of Actix Handler for Get message.
 impl Handler<Get> for RedisBackend {
-    type Result = ResponseFuture<Result<Option<Vec<u8>>, BackendError>>;
+    type Result = ResponseFuture<Result<Option<Bytes>, BackendError>>;
 
     fn handle(&mut self, msg: Get, _: &mut Self::Context) -> Self::Result {
-        let mut con = self.connection.clone();
+        let mut con = self.connection();
+        let max_key_length = self.max_key_length;
+        let storage = self.storage;
+        let sliding_ttl = self.sliding_ttl;
         let fut = async move {
-            redis::cmd("GET")
-                .arg(msg.key)
-                .query_async(&mut con)
+            check_key_length(&msg.key, max_key_length)?;
+            let value = match (storage, sliding_ttl) {
+                (RedisStorage::String, None) => {
+                    redis::cmd("GET")
+                        .arg(msg.key)
+                        .query_async::<_, Option<Vec<u8>>>(&mut con)
+                        .await
+                }
+                (RedisStorage::String, Some(ttl)) => {
+                    redis::cmd("GETEX")
+                        .arg(msg.key)
+                        .arg("PX")
+                        .arg(ttl.as_millis() as u64)
+                        .query_async::<_, Option<Vec<u8>>>(&mut con)
+                        .await
+                }
+                (RedisStorage::Hash, None) => {
+                    redis::cmd("HGET")
+                        .arg(msg.key)
+                        .arg(VALUE_FIELD)
+                        .query_async::<_, Option<Vec<u8>>>(&mut con)
+                        .await
+                }
+                (RedisStorage::Hash, Some(ttl)) => redis::pipe()
+                    .atomic()
+                    .cmd("HGET")
+                    .arg(&msg.key)
+                    .arg(VALUE_FIELD)
+                    .cmd("PEXPIRE")
+                    .arg(&msg.key)
+                    .arg(ttl.as_millis() as u64)
+                    .ignore()
+                    .query_async::<_, (Option<Vec<u8>>,)>(&mut con)
+                    .await
+                    .map(|(value,)| value),
+            };
+            value
+                .map(|value| value.map(Bytes::from))
+                .map_err(Error::from)
+                .map_err(BackendError::from)
+        };
+        Box::pin(fut)
+    }
+}
+
+/// Implementation of Actix Handler for Peek message.
+///
+/// The value is read with plain `GET` or `HGET` command, so sliding TTL isn't applied.
+impl Handler<Peek> for RedisBackend {
+    type Result = ResponseFuture<Result<Option<Bytes>, BackendError>>;
+
+    fn handle(&mut self, msg: Peek, _: &mut Self::Context) -> Self::Result {
+        let mut con = self.connection();
+        let max_key_length = self.max_key_length;
+        let storage = self.storage;
+        let fut = async move {
+            check_key_length(&msg.key, max_key_length)?;
+            let mut command = match storage {
+                RedisStorage::String => redis::cmd("GET"),
+                RedisStorage::Hash => redis::cmd("HGET"),
+            };
+            command.arg(msg.key);
+            if storage == RedisStorage::Hash {
+                command.arg(VALUE_FIELD);
+            }
+            command
+                .query_async::<_, Option<Vec<u8>>>(&mut con)
                 .await
+                .map(|value| value.map(Bytes::from))
                 .map_err(Error::from)
                 .map_err(BackendError::from)
         };
@@ -103,18 +363,45 @@ impl Handler<Set> for RedisBackend {
     type Result = ResponseFuture<Result<String, BackendError>>;
 
     fn handle(&mut self, msg: Set, _: &mut Self::Context) -> Self::Result {
-        let mut con = self.connection.clone();
+        let mut con = self.connection();
+        let max_key_length = self.max_key_length;
+        let storage = self.storage;
         Box::pin(async move {
-            let mut request = redis::cmd("SET");
-            request.arg(msg.key).arg(msg.value);
-            if let Some(ttl) = msg.ttl {
-                request.arg("EX").arg(ttl);
-            };
-            request
-                .query_async(&mut con)
-                .await
-                .map_err(Error::from)
-                .map_err(BackendError::from)
+            check_key_length(&msg.key, max_key_length)?;
+            match storage {
+                RedisStorage::String => {
+                    let mut request = redis::cmd("SET");
+                    request.arg(msg.key).arg(msg.value.as_ref());
+                    if let Some(ttl) = msg.ttl {
+                        request.arg("EX").arg(ttl);
+                    };
+                    request.query_async(&mut con).await
+                }
+                RedisStorage::Hash => {
+                    let created_at = now_millis();
+                    let mut pipe = redis::pipe();
+                    pipe.atomic().cmd("DEL").arg(&msg.key).ignore();
+                    pipe.cmd("HSET")
+                        .arg(&msg.key)
+                        .arg(VALUE_FIELD)
+                        .arg(msg.value.as_ref())
+                        .arg(CREATED_AT_FIELD)
+                        .arg(created_at);
+                    if let Some(stale_ttl) = msg.stale_ttl {
+                        pipe.arg(STALE_AT_FIELD)
+                            .arg(created_at + u64::from(stale_ttl) * 1000);
+                    }
+                    pipe.ignore();
+                    if let Some(ttl) = msg.ttl {
+                        pipe.cmd("EXPIRE").arg(&msg.key).arg(ttl).ignore();
+                    }
+                    pipe.query_async::<_, ()>(&mut con)
+                        .await
+                        .map(|_| "OK".to_owned())
+                }
+            }
+            .map_err(Error::from)
+            .map_err(BackendError::from)
         })
     }
 }
@@ -124,8 +411,10 @@ impl Handler<Delete> for RedisBackend {
     type Result = ResponseFuture<Result<DeleteStatus, BackendError>>;
 
     fn handle(&mut self, msg: Delete, _: &mut Self::Context) -> Self::Result {
-        let mut con = self.connection.clone();
+        let mut con = self.connection();
+        let max_key_length = self.max_key_length;
         Box::pin(async move {
+            check_key_length(&msg.key, max_key_length)?;
             redis::cmd("DEL")
                 .arg(msg.key)
                 .query_async(&mut con)
@@ -149,8 +438,10 @@ impl Handler<Lock> for RedisBackend {
 
     fn handle(&mut self, msg: Lock, _: &mut Self::Context) -> Self::Result {
         debug!("Redis Lock: {}", msg.key);
-        let mut con = self.connection.clone();
+        let mut con = self.connection();
+        let max_key_length = self.max_key_length;
         Box::pin(async move {
+            check_key_length(&msg.key, max_key_length)?;
             redis::cmd("SET")
                 .arg(format!("lock::{}", msg.key))
                 .arg("")
@@ -171,3 +462,205 @@ impl Handler<Lock> for RedisBackend {
         })
     }
 }
+
+/// Implementation of Actix Handler for Ping message.
+impl Handler<Ping> for RedisBackend {
+    type Result = ResponseFuture<Result<(), BackendError>>;
+
+    fn handle(&mut self, _: Ping, _: &mut Self::Context) -> Self::Result {
+        let mut con = self.connection();
+        Box::pin(async move {
+            redis::cmd("PING")
+                .query_async::<_, String>(&mut con)
+                .await
+                .map(|_| ())
+                .map_err(Error::from)
+                .map_err(BackendError::from)
+        })
+    }
+}
+
+/// Implementation of Actix Handler for GetMetadata message.
+impl Handler<GetMetadata> for RedisBackend {
+    type Result = ResponseFuture<Result<Option<RecordMetadata>, BackendError>>;
+
+    fn handle(&mut self, msg: GetMetadata, _: &mut Self::Context) -> Self::Result {
+        let mut con = self.connection();
+        let max_key_length = self.max_key_length;
+        let storage = self.storage;
+        Box::pin(async move {
+            check_key_length(&msg.key, max_key_length)?;
+            let mut pipe = redis::pipe();
+            pipe.cmd("PTTL").arg(&msg.key);
+            let result = match storage {
+                RedisStorage::String => pipe
+                    .query_async::<_, (i64,)>(&mut con)
+                    .await
+                    .map(|(ttl,)| (ttl, None, None)),
+                RedisStorage::Hash => pipe
+                    .cmd("HMGET")
+                    .arg(&msg.key)
+                    .arg(CREATED_AT_FIELD)
+                    .arg(STALE_AT_FIELD)
+                    .query_async::<_, (i64, (Option<u64>, Option<u64>))>(&mut con)
+                    .await
+                    .map(|(ttl, (created_at, stale_at))| (ttl, created_at, stale_at)),
+            };
+            let (ttl, created_at, stale_at) =
+                result.map_err(Error::from).map_err(BackendError::from)?;
+            // PTTL returns -2 for missing keys and -1 for keys without expiration.
+            let ttl = match ttl {
+                -2 => return Ok(None),
+                ttl if ttl < 0 => None,
+                ttl => Some(Duration::from_millis(ttl as u64)),
+            };
+            Ok(Some(RecordMetadata {
+                ttl,
+                created_at,
+                stale_at,
+            }))
+        })
+    }
+}
+
+/// Number of keys checked by each `SCAN` call.
+const SCAN_COUNT: usize = 1000;
+
+/// Iterates over the keys matching the pattern with `SCAN` command.
+///
+/// Stops after at least `limit` keys are found, returns the keys and the next cursor,
+/// which is `0` if the iteration is finished.
+async fn scan_keys(
+    con: &mut RedisConnection,
+    pattern: &str,
+    mut cursor: u64,
+    limit: Option<usize>,
+) -> Result<(Vec<String>, u64), Error> {
+    let count = limit.map_or(SCAN_COUNT, |limit| limit.clamp(1, SCAN_COUNT));
+    let mut keys = Vec::new();
+    loop {
+        let (next_cursor, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(pattern)
+            .arg("COUNT")
+            .arg(count)
+            .query_async(con)
+            .await?;
+        keys.extend(batch);
+        cursor = next_cursor;
+        if cursor == 0 || matches!(limit, Some(limit) if keys.len() >= limit) {
+            break;
+        }
+    }
+    // SCAN may return the same key several times.
+    keys.sort_unstable();
+    keys.dedup();
+    Ok((keys, cursor))
+}
+
+/// Returns memory used by the keys with pipelined `MEMORY USAGE` commands.
+async fn key_sizes(con: &mut RedisConnection, keys: &[String]) -> Result<Vec<Option<u64>>, Error> {
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut pipe = redis::pipe();
+    for key in keys {
+        pipe.cmd("MEMORY").arg("USAGE").arg(key);
+    }
+    Ok(pipe.query_async(con).await?)
+}
+
+/// Returns the value of the field from `INFO` command output.
+fn info_field(info: &str, field: &str) -> Option<u64> {
+    info.lines().find_map(|line| {
+        let (name, value) = line.split_at(line.find(':')?);
+        if name == field {
+            value[1..].trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+/// Implementation of Actix Handler for Scan message.
+///
+/// The cursor is the cursor of redis `SCAN` command.
+impl Handler<Scan> for RedisBackend {
+    type Result = ResponseFuture<Result<ScanPage, BackendError>>;
+
+    fn handle(&mut self, msg: Scan, _: &mut Self::Context) -> Self::Result {
+        debug!("Redis Scan: {}", msg.pattern);
+        let mut con = self.connection();
+        Box::pin(async move {
+            let cursor = match msg.cursor {
+                Some(cursor) => cursor
+                    .parse()
+                    .map_err(|error| BackendError::InternalError(Box::new(error)))?,
+                None => 0,
+            };
+            let (keys, cursor) = scan_keys(&mut con, &msg.pattern, cursor, msg.limit)
+                .await
+                .map_err(BackendError::from)?;
+            Ok(ScanPage {
+                keys,
+                cursor: (cursor != 0).then(|| cursor.to_string()),
+            })
+        })
+    }
+}
+
+/// Implementation of Actix Handler for KeySizes message.
+///
+/// Sizes are taken from `MEMORY USAGE` command in a single pipeline.
+impl Handler<KeySizes> for RedisBackend {
+    type Result = ResponseFuture<Result<Vec<Option<u64>>, BackendError>>;
+
+    fn handle(&mut self, msg: KeySizes, _: &mut Self::Context) -> Self::Result {
+        let mut con = self.connection();
+        Box::pin(async move {
+            key_sizes(&mut con, &msg.keys)
+                .await
+                .map_err(BackendError::from)
+        })
+    }
+}
+
+/// Implementation of Actix Handler for Stats message.
+///
+/// Whole database statistics are taken from `DBSIZE` and `INFO` commands,
+/// `bytes` is the memory used by redis server. Records matching the pattern are
+/// found with `SCAN` and sized with `MEMORY USAGE` command.
+impl Handler<Stats> for RedisBackend {
+    type Result = ResponseFuture<Result<BackendStats, BackendError>>;
+
+    fn handle(&mut self, msg: Stats, _: &mut Self::Context) -> Self::Result {
+        let mut con = self.connection();
+        Box::pin(async move {
+            let stats = async {
+                let info: String = redis::cmd("INFO").query_async(&mut con).await?;
+                let evictions = info_field(&info, "evicted_keys");
+                let stats = match msg.pattern {
+                    None => BackendStats {
+                        entries: redis::cmd("DBSIZE").query_async(&mut con).await?,
+                        bytes: info_field(&info, "used_memory").unwrap_or_default(),
+                        evictions,
+                    },
+                    Some(pattern) => {
+                        let (keys, _) = scan_keys(&mut con, &pattern, 0, None).await?;
+                        let usage = key_sizes(&mut con, &keys).await?;
+                        // Keys expired after the scan have no memory usage.
+                        let usage = usage.into_iter().flatten();
+                        BackendStats {
+                            entries: usage.clone().count() as u64,
+                            bytes: usage.sum(),
+                            evictions,
+                        }
+                    }
+                };
+                Ok::<_, Error>(stats)
+            };
+            stats.await.map_err(BackendError::from)
+        })
+    }
+}
@@ -0,0 +1,349 @@
+//! Redis connections used by [RedisBackend].
+//!
+//! [RedisBackend]: crate::RedisBackend
+use std::io;
+use std::time::Duration;
+#[cfg(feature = "tls")]
+use std::{future::Future, pin::Pin, sync::Arc};
+
+#[cfg(feature = "tls")]
+use redis::aio::MultiplexedConnection;
+use redis::aio::{ConnectionLike, ConnectionManager};
+#[cfg(feature = "tls")]
+use redis::RedisConnectionInfo;
+use redis::{Client, Cmd, ConnectionInfo, Pipeline, RedisError, RedisFuture, Value};
+
+#[cfg(feature = "tls")]
+use crate::config::REDACTED;
+use crate::error::Error;
+
+#[derive(Clone)]
+enum Connection {
+    /// Connection which reconnects automatically after network errors.
+    Managed(ConnectionManager),
+    /// Connection established over custom TLS stream.
+    #[cfg(feature = "tls")]
+    Reconnecting(ReconnectingConnection),
+}
+
+/// Multiplexed connection to redis node with optional response timeout.
+#[derive(Clone)]
+pub(crate) struct RedisConnection {
+    connection: Connection,
+    response_timeout: Option<Duration>,
+}
+
+impl RedisConnection {
+    /// Establishes reconnecting connection with the settings of [Client].
+    pub(crate) async fn connect(connection_info: &ConnectionInfo) -> Result<Self, Error> {
+        let client = Client::open(connection_info.clone())?;
+        let connection = client.get_tokio_connection_manager().await?;
+        Ok(Self {
+            connection: Connection::Managed(connection),
+            response_timeout: None,
+        })
+    }
+
+    /// Establishes connection over TLS stream with custom certificates.
+    ///
+    /// Like [RedisConnection::connect], the connection is established again
+    /// after it's dropped.
+    #[cfg(feature = "tls")]
+    pub(crate) async fn connect_tls(
+        connection_info: &ConnectionInfo,
+        tls: &TlsSettings,
+    ) -> Result<Self, Error> {
+        use redis::ConnectionAddr;
+
+        let (host, port, insecure) = match &connection_info.addr {
+            ConnectionAddr::Tcp(host, port) => (host, *port, false),
+            ConnectionAddr::TcpTls {
+                host,
+                port,
+                insecure,
+            } => (host, *port, *insecure),
+            ConnectionAddr::Unix(_) => {
+                return Err(Error::Config(
+                    "TLS certificates can't be used with unix socket connection".to_owned(),
+                ))
+            }
+        };
+        let mut builder = native_tls::TlsConnector::builder();
+        for certificate in &tls.ca_certificates {
+            builder.add_root_certificate(native_tls::Certificate::from_pem(certificate)?);
+        }
+        if let Some((identity, password)) = &tls.identity {
+            builder.identity(native_tls::Identity::from_pkcs12(identity, password)?);
+        }
+        if insecure {
+            builder
+                .danger_accept_invalid_certs(true)
+                .danger_accept_invalid_hostnames(true);
+        }
+        let connector = tokio_native_tls::TlsConnector::from(builder.build()?);
+        let host = host.clone();
+        let redis_info = connection_info.redis.clone();
+        let connect = move || -> ConnectFuture {
+            let (connector, host, redis_info) =
+                (connector.clone(), host.clone(), redis_info.clone());
+            Box::pin(async move {
+                let stream = tokio::net::TcpStream::connect((host.as_str(), port)).await?;
+                let stream = connector
+                    .connect(&host, stream)
+                    .await
+                    .map_err(|error| io::Error::new(io::ErrorKind::ConnectionAborted, error))?;
+                multiplexed(&redis_info, stream).await
+            })
+        };
+        let connection =
+            ReconnectingConnection::new(Arc::new(connect), connection_info.redis.db).await?;
+        Ok(Self {
+            connection: Connection::Reconnecting(connection),
+            response_timeout: None,
+        })
+    }
+
+    /// Sets the limit of each command response duration.
+    pub(crate) fn with_response_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.response_timeout = timeout;
+        self
+    }
+}
+
+/// Fails the request with timed out I/O error, if it isn't completed in time.
+fn with_timeout<'a, T: Send + 'a>(
+    request: RedisFuture<'a, T>,
+    timeout: Option<Duration>,
+) -> RedisFuture<'a, T> {
+    match timeout {
+        Some(timeout) => Box::pin(async move {
+            tokio::time::timeout(timeout, request).await.map_err(|_| {
+                RedisError::from(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "Redis response timeout",
+                ))
+            })?
+        }),
+        None => request,
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        let request = match &mut self.connection {
+            Connection::Managed(connection) => connection.req_packed_command(cmd),
+            #[cfg(feature = "tls")]
+            Connection::Reconnecting(connection) => connection.req_packed_command(cmd),
+        };
+        with_timeout(request, self.response_timeout)
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        let request = match &mut self.connection {
+            Connection::Managed(connection) => connection.req_packed_commands(cmd, offset, count),
+            #[cfg(feature = "tls")]
+            Connection::Reconnecting(connection) => {
+                connection.req_packed_commands(cmd, offset, count)
+            }
+        };
+        with_timeout(request, self.response_timeout)
+    }
+
+    fn get_db(&self) -> i64 {
+        match &self.connection {
+            Connection::Managed(connection) => connection.get_db(),
+            #[cfg(feature = "tls")]
+            Connection::Reconnecting(connection) => connection.get_db(),
+        }
+    }
+}
+
+/// Future establishing new multiplexed connection.
+#[cfg(feature = "tls")]
+type ConnectFuture =
+    Pin<Box<dyn Future<Output = Result<MultiplexedConnection, RedisError>> + Send>>;
+
+/// Creates multiplexed connection over the stream and spawns its driver.
+#[cfg(feature = "tls")]
+async fn multiplexed<S>(
+    connection_info: &RedisConnectionInfo,
+    stream: S,
+) -> Result<MultiplexedConnection, RedisError>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Send + Unpin + 'static,
+{
+    let (connection, driver) = MultiplexedConnection::new(connection_info, stream).await?;
+    actix_rt::spawn(driver);
+    Ok(connection)
+}
+
+/// Multiplexed connection which is established again by `connect` after it's dropped.
+///
+/// Like [ConnectionManager], the request which detected the dropped connection fails,
+/// the next request establishes new connection.
+#[cfg(feature = "tls")]
+#[derive(Clone)]
+struct ReconnectingConnection {
+    connect: Arc<dyn Fn() -> ConnectFuture + Send + Sync>,
+    state: Arc<tokio::sync::Mutex<ConnectionState>>,
+    db: i64,
+}
+
+/// Current connection and the number of established connections.
+#[cfg(feature = "tls")]
+struct ConnectionState {
+    generation: u64,
+    connection: Option<MultiplexedConnection>,
+}
+
+#[cfg(feature = "tls")]
+impl ReconnectingConnection {
+    /// Establishes the first connection.
+    async fn new(
+        connect: Arc<dyn Fn() -> ConnectFuture + Send + Sync>,
+        db: i64,
+    ) -> Result<Self, RedisError> {
+        let connection = connect().await?;
+        let state = ConnectionState {
+            generation: 0,
+            connection: Some(connection),
+        };
+        Ok(Self {
+            connect,
+            state: Arc::new(tokio::sync::Mutex::new(state)),
+            db,
+        })
+    }
+
+    /// Returns the current connection and its generation, connecting again if it was dropped.
+    async fn current(&self) -> Result<(u64, MultiplexedConnection), RedisError> {
+        let mut state = self.state.lock().await;
+        let connection = match &state.connection {
+            Some(connection) => connection.clone(),
+            None => {
+                let connection = (self.connect)().await?;
+                state.generation += 1;
+                state.connection = Some(connection.clone());
+                connection
+            }
+        };
+        Ok((state.generation, connection))
+    }
+
+    /// Forgets the connection of `generation` if the result shows it was dropped.
+    async fn check<T>(&self, generation: u64, result: &Result<T, RedisError>) {
+        if let Err(error) = result {
+            if error.is_connection_dropped() {
+                let mut state = self.state.lock().await;
+                if state.generation == generation {
+                    state.connection = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tls")]
+impl ConnectionLike for ReconnectingConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let (generation, mut connection) = self.current().await?;
+            let result = connection.req_packed_command(cmd).await;
+            self.check(generation, &result).await;
+            result
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let (generation, mut connection) = self.current().await?;
+            let result = connection.req_packed_commands(cmd, offset, count).await;
+            self.check(generation, &result).await;
+            result
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.db
+    }
+}
+
+/// Custom certificates of TLS connection.
+#[cfg(feature = "tls")]
+#[derive(Clone, Default)]
+pub(crate) struct TlsSettings {
+    /// Additional trusted root certificates in PEM format.
+    pub(crate) ca_certificates: Vec<Vec<u8>>,
+    /// Client identity in PKCS #12 format and its password.
+    pub(crate) identity: Option<(Vec<u8>, String)>,
+}
+
+#[cfg(feature = "tls")]
+impl std::fmt::Debug for TlsSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsSettings")
+            .field("ca_certificates", &self.ca_certificates.len())
+            .field("identity", &self.identity.as_ref().map(|_| REDACTED))
+            .finish()
+    }
+}
+
+#[cfg(feature = "tls")]
+impl TlsSettings {
+    /// Returns `true` if default TLS settings of redis client can't be used.
+    pub(crate) fn is_custom(&self) -> bool {
+        !self.ca_certificates.is_empty() || self.identity.is_some()
+    }
+}
+
+#[cfg(all(test, feature = "tls"))]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Answers `PONG` to the first command of each connection and closes it.
+    async fn serve_once(listener: TcpListener) {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 64];
+            let _ = stream.read(&mut buffer).await.unwrap();
+            stream.write_all(b"+PONG\r\n").await.unwrap();
+        }
+    }
+
+    async fn ping(connection: &mut ReconnectingConnection) -> Result<String, RedisError> {
+        redis::cmd("PING").query_async(connection).await
+    }
+
+    #[actix_rt::test]
+    async fn test_reconnect_after_connection_drop() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        actix_rt::spawn(serve_once(listener));
+        let connect = move || -> ConnectFuture {
+            Box::pin(async move {
+                let stream = TcpStream::connect(addr).await?;
+                multiplexed(&RedisConnectionInfo::default(), stream).await
+            })
+        };
+        let mut connection = ReconnectingConnection::new(Arc::new(connect), 0)
+            .await
+            .unwrap();
+
+        assert_eq!(ping(&mut connection).await.unwrap(), "PONG");
+        let error = ping(&mut connection).await.unwrap_err();
+        assert!(error.is_connection_dropped());
+        assert_eq!(ping(&mut connection).await.unwrap(), "PONG");
+    }
+}
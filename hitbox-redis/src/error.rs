@@ -14,6 +14,19 @@ pub enum Error {
     /// Wrapper for all kinds redis-rs errors.
     #[error("Redis backend error: {0}")]
     Redis(RedisError),
+    /// Connection wasn't established in time.
+    #[error("Redis connection timeout")]
+    ConnectTimeout,
+    /// Wrapper for TLS connector errors.
+    #[cfg(feature = "tls")]
+    #[error("Redis TLS error: {0}")]
+    Tls(native_tls::Error),
+    /// Reading of configuration files failed.
+    #[error("Redis backend configuration reading error: {0}")]
+    Io(std::io::Error),
+    /// Invalid combination of backend settings.
+    #[error("Redis backend configuration error: {0}")]
+    Config(String),
 }
 
 impl From<RedisError> for Error {
@@ -22,8 +35,34 @@ impl From<RedisError> for Error {
     }
 }
 
+#[cfg(feature = "tls")]
+impl From<native_tls::Error> for Error {
+    fn from(error: native_tls::Error) -> Self {
+        Error::Tls(error)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
 impl From<Error> for BackendError {
     fn from(error: Error) -> Self {
-        Self::InternalError(Box::new(error))
+        match error {
+            Error::Redis(ref redis_error)
+                if redis_error.is_io_error()
+                    || redis_error.is_timeout()
+                    || redis_error.is_connection_refusal()
+                    || redis_error.is_connection_dropped() =>
+            {
+                Self::ConnectionError(Box::new(error))
+            }
+            Error::ConnectTimeout => Self::ConnectionError(Box::new(error)),
+            #[cfg(feature = "tls")]
+            Error::Tls(_) => Self::ConnectionError(Box::new(error)),
+            _ => Self::InternalError(Box::new(error)),
+        }
     }
 }
@@ -8,7 +8,11 @@
 //! [Backend]: hitbox_backend::Backend
 //! [redis-rs]: redis-rs::aio
 pub mod actor;
+pub mod config;
+mod connection;
 pub mod error;
 
 #[doc(inline)]
-pub use crate::actor::{RedisBackend, RedisBackendBuilder};
+pub use crate::actor::{RedisBackend, RedisBackendBuilder, RedisStorage};
+#[doc(inline)]
+pub use crate::config::RedisConfig;
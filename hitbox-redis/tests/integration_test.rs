@@ -1,6 +1,9 @@
 use actix::prelude::*;
-use hitbox_backend::{Delete, DeleteStatus, Get, Lock, LockStatus, Set};
-use hitbox_redis::{error::Error, RedisBackend};
+use hitbox_backend::{
+    Bytes, Delete, DeleteStatus, Get, GetMetadata, KeySizes, Lock, LockStatus, Peek, Ping, Scan,
+    Set, Stats,
+};
+use hitbox_redis::{error::Error, RedisBackend, RedisStorage};
 use tokio::time::{sleep, Duration};
 
 #[actix_rt::test]
@@ -8,8 +11,9 @@ async fn test_rw() -> Result<(), Error> {
     let addr = RedisBackend::new().await?.start();
     let message = Set {
         key: "key".to_owned(),
-        value: b"value".to_vec(),
+        value: Bytes::from_static(b"value"),
         ttl: None,
+        stale_ttl: None,
     };
     let res = addr.send(message.clone()).await.unwrap().unwrap();
     assert_eq!(res, "OK");
@@ -30,8 +34,9 @@ async fn test_set_expired() -> Result<(), Error> {
     let addr = RedisBackend::new().await?.start();
     let message = Set {
         key: "key_expired".to_owned(),
-        value: b"value".to_vec(),
+        value: Bytes::from_static(b"value"),
         ttl: Some(1),
+        stale_ttl: None,
     };
     let res = addr.send(message.clone()).await.unwrap().unwrap();
     assert_eq!(res, "OK");
@@ -59,8 +64,9 @@ async fn test_delete() -> Result<(), Error> {
     let addr = RedisBackend::new().await?.start();
     let message = Set {
         key: "another_key".to_owned(),
-        value: b"value".to_vec(),
+        value: Bytes::from_static(b"value"),
         ttl: Some(1),
+        stale_ttl: None,
     };
     let res = addr.send(message.clone()).await.unwrap().unwrap();
     assert_eq!(res, "OK");
@@ -106,3 +112,201 @@ async fn test_lock() -> Result<(), Error> {
     assert_eq!(res, LockStatus::Acquired);
     Ok(())
 }
+
+#[actix_rt::test]
+async fn test_ping() -> Result<(), Error> {
+    let addr = RedisBackend::new().await?.start();
+    let res = addr.send(Ping).await.unwrap();
+    assert!(res.is_ok());
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_connection_pool() -> Result<(), Error> {
+    let addr = RedisBackend::builder()
+        .pool_size(3)
+        .database(1)
+        .connect_timeout(Duration::from_secs(1))
+        .response_timeout(Duration::from_secs(1))
+        .build()
+        .await?
+        .start();
+    for index in 0..6 {
+        let message = Set {
+            key: format!("pool_key_{}", index),
+            value: Bytes::from_static(b"value"),
+            ttl: Some(10),
+            stale_ttl: None,
+        };
+        addr.send(message.clone()).await.unwrap().unwrap();
+        let res = addr.send(Get { key: message.key }).await;
+        assert_eq!(res.unwrap().unwrap(), Some(message.value));
+    }
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_hash_storage() -> Result<(), Error> {
+    let addr = RedisBackend::builder()
+        .storage(RedisStorage::Hash)
+        .build()
+        .await?
+        .start();
+    let message = Set {
+        key: "hash_key".to_owned(),
+        value: Bytes::from_static(b"value"),
+        ttl: Some(10),
+        stale_ttl: Some(5),
+    };
+    let res = addr.send(message.clone()).await.unwrap().unwrap();
+    assert_eq!(res, "OK");
+    let res = addr
+        .send(Get {
+            key: message.key.clone(),
+        })
+        .await;
+    assert_eq!(res.unwrap().unwrap(), Some(message.value));
+
+    let metadata = addr
+        .send(GetMetadata {
+            key: message.key.clone(),
+        })
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(metadata.ttl.unwrap() <= Duration::from_secs(10));
+    let created_at = metadata.created_at.unwrap();
+    assert_eq!(metadata.stale_at, Some(created_at + 5000));
+    assert_eq!(metadata.is_stale(), Some(false));
+
+    addr.send(Delete { key: message.key })
+        .await
+        .unwrap()
+        .unwrap();
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_metadata_missing() -> Result<(), Error> {
+    let addr = RedisBackend::new().await?.start();
+    let res = addr
+        .send(GetMetadata {
+            key: "missing_metadata_key".to_owned(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(res.is_none());
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_sliding_ttl() -> Result<(), Error> {
+    let addr = RedisBackend::builder()
+        .sliding_ttl(Duration::from_secs(60))
+        .build()
+        .await?
+        .start();
+    let message = Set {
+        key: "sliding_key".to_owned(),
+        value: Bytes::from_static(b"value"),
+        ttl: Some(1),
+        stale_ttl: None,
+    };
+    addr.send(message.clone()).await.unwrap().unwrap();
+    let res = addr
+        .send(Get {
+            key: message.key.clone(),
+        })
+        .await;
+    assert_eq!(res.unwrap().unwrap(), Some(message.value.clone()));
+
+    sleep(Duration::from_secs(1)).await;
+
+    let res = addr
+        .send(Get {
+            key: message.key.clone(),
+        })
+        .await;
+    assert_eq!(res.unwrap().unwrap(), Some(message.value.clone()));
+    addr.send(Delete {
+        key: message.key.clone(),
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    // Peek reads the value without sliding its TTL.
+    addr.send(message.clone()).await.unwrap().unwrap();
+    let res = addr
+        .send(Peek {
+            key: message.key.clone(),
+        })
+        .await;
+    assert_eq!(res.unwrap().unwrap(), Some(message.value));
+    let metadata = addr
+        .send(GetMetadata { key: message.key })
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(metadata.ttl.unwrap() <= Duration::from_secs(1));
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn test_scan_and_stats() -> Result<(), Error> {
+    let addr = RedisBackend::new().await?.start();
+    for index in 0..3 {
+        let message = Set {
+            key: format!("scan_key::{}", index),
+            value: Bytes::from_static(b"value"),
+            ttl: Some(10),
+            stale_ttl: None,
+        };
+        addr.send(message).await.unwrap().unwrap();
+    }
+    let mut scan = Scan {
+        pattern: "scan_key::*".to_owned(),
+        cursor: None,
+        limit: Some(1),
+    };
+    let mut keys = Vec::new();
+    loop {
+        let page = addr.send(scan.clone()).await.unwrap().unwrap();
+        keys.extend(page.keys);
+        match page.cursor {
+            Some(cursor) => scan.cursor = Some(cursor),
+            None => break,
+        }
+    }
+    keys.sort();
+    keys.dedup();
+    assert_eq!(keys, vec!["scan_key::0", "scan_key::1", "scan_key::2"]);
+
+    let sizes = addr
+        .send(KeySizes {
+            keys: vec!["scan_key::0".to_owned(), "missing".to_owned()],
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(sizes[0].unwrap() > 0);
+    assert_eq!(sizes[1], None);
+
+    let stats = addr
+        .send(Stats {
+            pattern: Some("scan_key::*".to_owned()),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats.entries, 3);
+    assert!(stats.bytes > 0);
+    assert!(stats.evictions.is_some());
+
+    let stats = addr.send(Stats { pattern: None }).await.unwrap().unwrap();
+    assert!(stats.entries >= 3);
+    Ok(())
+}
@@ -1,9 +1,13 @@
 //! Cacheable trait and implementation of cache logic.
 
-use crate::CacheError;
+use crate::settings::SettingsOverride;
+use crate::{CacheError, KeyHasher};
 #[cfg(feature = "derive")]
 pub use hitbox_derive::Cacheable;
 
+/// Default time-to-live of cached values in seconds, see [Cacheable::cache_ttl].
+pub const DEFAULT_CACHE_TTL: u32 = 60;
+
 /// Trait describes cache configuration per type that implements this trait.
 pub trait Cacheable {
     /// Method should return unique identifier for struct object.
@@ -40,11 +44,21 @@ pub trait Cacheable {
     /// Method return cache key prefix based on message type.
     fn cache_key_prefix(&self) -> String;
 
+    /// Describe the algorithm used to hash the serialized message in the cache key.
+    ///
+    /// If it returns `Some`, the serialized message is replaced with its hash,
+    /// the cache key prefix and version stay readable.
+    /// Keys generated by the derive macro respect this setting,
+    /// custom `cache_key` implementations should apply [KeyHasher::hash] by themselves.
+    fn cache_key_hasher(&self) -> Option<KeyHasher> {
+        None
+    }
+
     /// Describe time-to-live (ttl) value for cache storage in seconds.
     ///
     /// After that time value will be removed from cache storage.
     fn cache_ttl(&self) -> u32 {
-        60
+        DEFAULT_CACHE_TTL
     }
 
     /// Describe expire\stale timeout value for cache storage in seconds.
@@ -59,17 +73,21 @@ pub trait Cacheable {
     fn cache_stale_ttl(&self) -> u32 {
         let ttl = self.cache_ttl();
         let stale_time = 5;
-        if ttl >= stale_time {
-            ttl - stale_time
-        } else {
-            0
-        }
+        ttl.saturating_sub(stale_time)
     }
 
     /// Describe current cache version for this type.
     fn cache_version(&self) -> u32 {
         0
     }
+
+    /// Describe overrides of cache actor settings for this type.
+    ///
+    /// Allows to disable cache or stale mechanics for some messages only.
+    /// Not overridden settings are taken from the cache actor.
+    fn cache_settings(&self) -> SettingsOverride {
+        SettingsOverride::default()
+    }
 }
 
 #[cfg(test)]
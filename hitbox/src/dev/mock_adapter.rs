@@ -3,6 +3,8 @@ use crate::runtime::{AdapterResult, EvictionPolicy, RuntimeAdapter, TtlSettings}
 use crate::value::{CacheState, CachedValue};
 use crate::CacheableResponse;
 use chrono::{DateTime, Utc};
+use hitbox_backend::BackendError;
+use std::sync::{Arc, Mutex};
 
 #[derive(Clone, Debug)]
 /// Settings for builder.
@@ -30,6 +32,8 @@ where
     upstream_state: MockUpstreamState<T>,
     /// Cache state.
     cache_state: MockCacheState<T>,
+    /// Serialized values stored into cache, shared between clones.
+    cache_writes: Arc<Mutex<Vec<Vec<u8>>>>,
 }
 
 impl<T> MockAdapter<T>
@@ -43,6 +47,11 @@ where
             cache_state: MockCacheState::Error,
         }
     }
+
+    /// Returns serialized values stored into cache by this adapter and its clones.
+    pub fn cache_writes(&self) -> Vec<Vec<u8>> {
+        self.cache_writes.lock().unwrap().clone()
+    }
 }
 
 /// Implement builder pattern.
@@ -100,6 +109,7 @@ where
         MockAdapter {
             upstream_state: self.upstream_state,
             cache_state: self.cache_state,
+            cache_writes: Arc::new(Mutex::new(Vec::new())),
         }
     }
 }
@@ -112,7 +122,7 @@ where
     fn poll_upstream(&mut self) -> AdapterResult<Self::UpstreamResult> {
         let result = match self.clone().upstream_state {
             MockUpstreamState::Ok(value) => Ok(value),
-            MockUpstreamState::Error => Err(CacheError::DeserializeError),
+            MockUpstreamState::Error => Err(CacheError::from(actix::MailboxError::Closed)),
         };
         Box::pin(async { result })
     }
@@ -127,13 +137,18 @@ where
                 Ok(CacheState::Stale(CachedValue::new(value.0, value.1)))
             }
             MockCacheState::Miss => Ok(CacheState::Miss),
-            MockCacheState::Error => Err(CacheError::DeserializeError),
+            MockCacheState::Error => Err(CacheError::BackendError(BackendError::InternalError(
+                Box::new(std::fmt::Error),
+            ))),
         };
         Box::pin(async { result })
     }
 
-    fn update_cache(&self, _: &CachedValue<Self::UpstreamResult>) -> AdapterResult<()> {
-        Box::pin(async { Ok(()) })
+    fn update_cache(&self, cached_value: &CachedValue<Self::UpstreamResult>) -> AdapterResult<()> {
+        let result = cached_value
+            .serialize()
+            .map(|serialized| self.cache_writes.lock().unwrap().push(serialized));
+        Box::pin(async { result })
     }
 
     fn eviction_settings(&self) -> EvictionPolicy {
@@ -1,11 +1,16 @@
 //! Structures and traits for custom backend development and testing process.
-pub use hitbox_backend::{Backend, BackendError, Delete, DeleteStatus, Get, Lock, LockStatus, Set};
+pub use hitbox_backend::{
+    check_key_length, matches_pattern, now_millis, Backend, BackendError, BackendStats, Bytes,
+    Delete, DeleteStatus, Get, GetMetadata, KeySizes, Lock, LockStatus, Peek, Ping, RecordMetadata,
+    Scan, ScanPage, Set, Stats,
+};
 
 #[doc(hidden)]
 /// Mocked backend implementation module.
 pub mod backend {
     use super::*;
     use actix::prelude::*;
+    use std::collections::HashMap;
 
     #[derive(Debug, Clone, PartialEq)]
     pub enum MockMessage {
@@ -13,10 +18,19 @@ pub mod backend {
         Set(Set),
         Delete(Delete),
         Lock(Lock),
+        Ping(Ping),
+        Scan(Scan),
+        KeySizes(KeySizes),
+        Stats(Stats),
+        GetMetadata(GetMetadata),
+        Peek(Peek),
     }
 
     pub struct MockBackend {
         pub messages: Vec<MockMessage>,
+        pub storage: HashMap<String, Bytes>,
+        pub metadata: HashMap<String, RecordMetadata>,
+        pub max_key_length: Option<usize>,
     }
 
     impl MockBackend {
@@ -29,6 +43,9 @@ pub mod backend {
         fn default() -> Self {
             MockBackend {
                 messages: Vec::with_capacity(10),
+                storage: HashMap::new(),
+                metadata: HashMap::new(),
+                max_key_length: None,
             }
         }
     }
@@ -46,8 +63,21 @@ pub mod backend {
         type Result = <Get as Message>::Result;
 
         fn handle(&mut self, msg: Get, _: &mut Self::Context) -> Self::Result {
+            check_key_length(&msg.key, self.max_key_length)?;
+            let value = self.storage.get(&msg.key).cloned();
             self.messages.push(MockMessage::Get(msg));
-            Ok(None)
+            Ok(value)
+        }
+    }
+
+    impl Handler<Peek> for MockBackend {
+        type Result = <Peek as Message>::Result;
+
+        fn handle(&mut self, msg: Peek, _: &mut Self::Context) -> Self::Result {
+            check_key_length(&msg.key, self.max_key_length)?;
+            let value = self.storage.get(&msg.key).cloned();
+            self.messages.push(MockMessage::Peek(msg));
+            Ok(value)
         }
     }
 
@@ -55,6 +85,19 @@ pub mod backend {
         type Result = <Set as Message>::Result;
 
         fn handle(&mut self, msg: Set, _: &mut Self::Context) -> Self::Result {
+            check_key_length(&msg.key, self.max_key_length)?;
+            let created_at = now_millis();
+            let metadata = RecordMetadata {
+                ttl: msg
+                    .ttl
+                    .map(|ttl| std::time::Duration::from_secs(u64::from(ttl))),
+                created_at: Some(created_at),
+                stale_at: msg
+                    .stale_ttl
+                    .map(|stale_ttl| created_at + u64::from(stale_ttl) * 1000),
+            };
+            self.storage.insert(msg.key.clone(), msg.value.clone());
+            self.metadata.insert(msg.key.clone(), metadata);
             self.messages.push(MockMessage::Set(msg));
             Ok("".to_owned())
         }
@@ -73,8 +116,81 @@ pub mod backend {
         type Result = <Delete as Message>::Result;
 
         fn handle(&mut self, msg: Delete, _: &mut Self::Context) -> Self::Result {
+            self.metadata.remove(&msg.key);
+            let status = match self.storage.remove(&msg.key) {
+                Some(_) => DeleteStatus::Deleted(1),
+                None => DeleteStatus::Missing,
+            };
             self.messages.push(MockMessage::Delete(msg));
-            Ok(DeleteStatus::Missing)
+            Ok(status)
+        }
+    }
+
+    impl Handler<Ping> for MockBackend {
+        type Result = <Ping as Message>::Result;
+
+        fn handle(&mut self, msg: Ping, _: &mut Self::Context) -> Self::Result {
+            self.messages.push(MockMessage::Ping(msg));
+            Ok(())
+        }
+    }
+
+    impl Handler<GetMetadata> for MockBackend {
+        type Result = <GetMetadata as Message>::Result;
+
+        fn handle(&mut self, msg: GetMetadata, _: &mut Self::Context) -> Self::Result {
+            check_key_length(&msg.key, self.max_key_length)?;
+            let metadata = self.metadata.get(&msg.key).cloned();
+            self.messages.push(MockMessage::GetMetadata(msg));
+            Ok(metadata)
+        }
+    }
+
+    impl Handler<Scan> for MockBackend {
+        type Result = <Scan as Message>::Result;
+
+        fn handle(&mut self, msg: Scan, _: &mut Self::Context) -> Self::Result {
+            let page = msg.page(self.storage.keys());
+            self.messages.push(MockMessage::Scan(msg));
+            Ok(page)
+        }
+    }
+
+    impl Handler<KeySizes> for MockBackend {
+        type Result = <KeySizes as Message>::Result;
+
+        fn handle(&mut self, msg: KeySizes, _: &mut Self::Context) -> Self::Result {
+            let sizes = msg
+                .keys
+                .iter()
+                .map(|key| {
+                    self.storage
+                        .get(key)
+                        .map(|value| (key.len() + value.len()) as u64)
+                })
+                .collect();
+            self.messages.push(MockMessage::KeySizes(msg));
+            Ok(sizes)
+        }
+    }
+
+    impl Handler<Stats> for MockBackend {
+        type Result = <Stats as Message>::Result;
+
+        fn handle(&mut self, msg: Stats, _: &mut Self::Context) -> Self::Result {
+            let mut stats = BackendStats::default();
+            for (key, value) in &self.storage {
+                let matched = match &msg.pattern {
+                    Some(pattern) => matches_pattern(pattern, key),
+                    None => true,
+                };
+                if matched {
+                    stats.entries += 1;
+                    stats.bytes += (key.len() + value.len()) as u64;
+                }
+            }
+            self.messages.push(MockMessage::Stats(msg));
+            Ok(stats)
         }
     }
 
@@ -2,5 +2,9 @@
 mod mock_adapter;
 pub mod mock_backend;
 
-pub use hitbox_backend::{Backend, BackendError, Delete, DeleteStatus, Get, Lock, LockStatus, Set};
+pub use hitbox_backend::{
+    escape_pattern, matches_pattern, now_millis, Backend, BackendError, BackendStats, Bytes,
+    Delete, DeleteStatus, Get, GetMetadata, KeySizes, Lock, LockStatus, Peek, Ping, RecordMetadata,
+    Scan, ScanPage, Set, Stats,
+};
 pub use mock_adapter::MockAdapter;
@@ -2,21 +2,67 @@
 use thiserror::Error;
 
 /// Base hitbox error.
+///
+/// Errors are divided into two groups:
+/// * Cache errors - all problems with cache key generation, cached data
+///   (de)serialization and cache backend interactions.
+///   Cache errors occurred during cache polling or updating are only logged,
+///   the value from the upstream is returned instead.
+///   So a cache error is returned only if the upstream wasn't polled at all
+///   (for example, cache key generation failed).
+/// * Upstream errors - the upstream was polled but didn't return a value.
 #[derive(Error, Debug)]
 pub enum CacheError {
     /// Error described all problems with cache backend interactions.
     #[error(transparent)]
     BackendError(#[from] hitbox_backend::BackendError),
-    /// Wrapper for upstream actor mailbox or timeout errors.
-    #[error("Actix upstream actor error")]
-    UpstreamError(#[from] actix::MailboxError),
+    /// Upstream transport error (closed mailbox, timeout, etc.).
+    #[error("Upstream error: {0}")]
+    UpstreamError(#[source] Box<dyn std::error::Error + Send>),
     /// Wrapper for cache data serialization problems.
-    #[error("Cached data serialization error")]
-    SerializeError(#[from] serde_json::Error),
+    #[error("Cached data serialization error: {0}")]
+    SerializeError(#[source] serde_json::Error),
     /// Wrapper for cache data deserialization problems.
-    #[error("Cached data deserialization error")]
-    DeserializeError,
+    #[error("Cached data deserialization error for key {key}: {source}")]
+    DeserializeError {
+        /// Cache key of the broken record.
+        key: String,
+        /// Original deserialization error.
+        source: serde_json::Error,
+    },
+    /// Value can't be stored into cache because of its [`CachePolicy`](crate::CachePolicy).
+    #[error("Value is non cacheable")]
+    NonCacheableError,
     /// Wrapper error for problems with cache key generation.
-    #[error("Cache key generation error")]
+    #[error("Cache key generation error: {0}")]
     CacheKeyGenerationError(String),
 }
+
+impl CacheError {
+    /// Returns `true` if the upstream didn't return a value.
+    pub fn is_upstream_error(&self) -> bool {
+        matches!(self, CacheError::UpstreamError(_))
+    }
+
+    /// Returns `true` if the error is caused by cache layer, not by the upstream.
+    pub fn is_cache_error(&self) -> bool {
+        !self.is_upstream_error()
+    }
+
+    /// Returns `true` if the upstream didn't respond in time.
+    pub fn is_upstream_timeout(&self) -> bool {
+        match self {
+            CacheError::UpstreamError(error) => matches!(
+                error.downcast_ref::<actix::MailboxError>(),
+                Some(actix::MailboxError::Timeout)
+            ),
+            _ => false,
+        }
+    }
+}
+
+impl From<actix::MailboxError> for CacheError {
+    fn from(error: actix::MailboxError) -> Self {
+        CacheError::UpstreamError(Box::new(error))
+    }
+}
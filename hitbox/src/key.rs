@@ -0,0 +1,322 @@
+//! Cache key encoding and hashing.
+//!
+//! [KeyEncoder] turns the message into the variable part of the cache key.
+//! There are three encoders out of the box:
+//! * [QueryEncoder] - URL query string, the default encoder of the derive macro
+//!   (with `derive` feature). It has some
+//!   [restrictions](https://docs.rs/serde_qs/latest/serde_qs/#supported-types),
+//!   maps and nested enums aren't supported.
+//! * [JsonEncoder] - canonical JSON with sorted map keys, supports any `Serialize` type.
+//! * [HashEncoder] - hash calculated with the `Hash` implementation of the message.
+//!
+//! Keys built from serialized messages can be very long and contain user data.
+//! [KeyHasher] replaces the encoded message with its hash,
+//! the prefix and the version of the cache key are kept readable:
+//! `Message::v1::2e3b8e3a8b1c9d6f`.
+//!
+//! Every hasher is available under the feature of the same name (`xxh3`, `sha2`, `blake3`).
+use siphasher::sip::SipHasher13;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use std::sync::Arc;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::CacheError;
+
+/// Encodes values of type `T` into the cache key.
+///
+/// Encoder is selected in the derive macro by `cache_key_encoder` attribute:
+/// ```edition2018,ignore
+/// #[derive(Cacheable, Serialize)]
+/// #[cache_key_encoder(JsonEncoder)]
+/// struct Message {
+///     filters: HashMap<String, String>,
+/// }
+/// ```
+pub trait KeyEncoder<T: ?Sized> {
+    /// Encodes the whole value.
+    fn encode(value: &T) -> Result<String, CacheError>;
+
+    /// Encodes the named field of the message, used when the message is encoded field by field.
+    ///
+    /// Default implementation returns `name=encoded value`.
+    fn encode_field(name: &str, value: &T) -> Result<String, CacheError> {
+        Self::encode(value).map(|value| format!("{}={}", name, value))
+    }
+}
+
+/// URL query string encoder based on serde_qs crate.
+#[cfg(feature = "derive")]
+#[derive(Debug, Clone, Copy)]
+pub struct QueryEncoder;
+
+#[cfg(feature = "derive")]
+impl<T: Serialize + ?Sized> KeyEncoder<T> for QueryEncoder {
+    fn encode(value: &T) -> Result<String, CacheError> {
+        serde_qs::to_string(&value)
+            .map_err(|error| CacheError::CacheKeyGenerationError(error.to_string()))
+    }
+
+    fn encode_field(name: &str, value: &T) -> Result<String, CacheError> {
+        let field = std::iter::once((name, value)).collect::<std::collections::BTreeMap<_, _>>();
+        serde_qs::to_string(&field)
+            .map_err(|error| CacheError::CacheKeyGenerationError(error.to_string()))
+    }
+}
+
+/// Canonical JSON encoder, keys of maps and structs are sorted.
+///
+/// Map keys should be strings or integers.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonEncoder;
+
+impl<T: Serialize + ?Sized> KeyEncoder<T> for JsonEncoder {
+    fn encode(value: &T) -> Result<String, CacheError> {
+        serde_json::to_value(value)
+            .map(canonicalize)
+            .map(|value| value.to_string())
+            .map_err(|error| CacheError::CacheKeyGenerationError(error.to_string()))
+    }
+}
+
+/// Rebuilds objects with sorted keys, regardless of `preserve_order` feature of serde_json.
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(left, _), (right, _)| left.cmp(right));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        value => value,
+    }
+}
+
+/// Fixed SipHash keys of [HashEncoder], changing them invalidates cached values.
+const HASH_ENCODER_KEYS: (u64, u64) = (0x6869_7462_6f78_6b65, 0x7968_6173_6865_7273);
+
+/// Encoder based on `Hash` implementation of the value.
+///
+/// Uses SipHash-1-3 of the `siphasher` crate with fixed keys, so the hash doesn't change
+/// between Rust releases. Note that `Hash` implementations of `usize` and `isize`
+/// depend on the platform pointer width.
+#[derive(Debug, Clone, Copy)]
+pub struct HashEncoder;
+
+impl<T: Hash + ?Sized> KeyEncoder<T> for HashEncoder {
+    fn encode(value: &T) -> Result<String, CacheError> {
+        let mut hasher = SipHasher13::new_with_keys(HASH_ENCODER_KEYS.0, HASH_ENCODER_KEYS.1);
+        value.hash(&mut hasher);
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+}
+
+/// Stable name of the type in cache key prefixes.
+///
+/// Derived [Cacheable] implementations of generic types append names of type parameters
+/// to the prefix, so `Query<i32>` and `Query<String>` don't share cached values.
+/// Unlike `std::any::type_name`, the name is defined by the implementation and doesn't
+/// change between compiler releases. It's implemented for primitives and common std types,
+/// other type parameters should implement it explicitly:
+/// ```
+/// use hitbox::KeyTypeName;
+///
+/// struct UserId(i32);
+///
+/// impl KeyTypeName for UserId {
+///     fn key_type_name() -> String {
+///         "UserId".to_owned()
+///     }
+/// }
+///
+/// assert_eq!(<Vec<UserId>>::key_type_name(), "Vec<UserId>");
+/// ```
+///
+/// [Cacheable]: crate::Cacheable
+pub trait KeyTypeName {
+    /// Returns the name of the type used in cache keys.
+    fn key_type_name() -> String;
+}
+
+macro_rules! impl_key_type_name {
+    ($($ty:ty),*) => {
+        $(
+            impl KeyTypeName for $ty {
+                fn key_type_name() -> String {
+                    stringify!($ty).to_owned()
+                }
+            }
+        )*
+    };
+}
+
+impl_key_type_name!(
+    (),
+    bool,
+    char,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    str,
+    String
+);
+
+macro_rules! impl_generic_key_type_name {
+    ($($name:ident),*) => {
+        $(
+            impl<T: KeyTypeName + ?Sized> KeyTypeName for $name<T> {
+                fn key_type_name() -> String {
+                    format!("{}<{}>", stringify!($name), T::key_type_name())
+                }
+            }
+        )*
+    };
+}
+
+impl_generic_key_type_name!(Box, Rc, Arc);
+
+impl<T: KeyTypeName> KeyTypeName for Vec<T> {
+    fn key_type_name() -> String {
+        format!("Vec<{}>", T::key_type_name())
+    }
+}
+
+impl<T: KeyTypeName> KeyTypeName for Option<T> {
+    fn key_type_name() -> String {
+        format!("Option<{}>", T::key_type_name())
+    }
+}
+
+impl<T: KeyTypeName + ?Sized> KeyTypeName for &T {
+    fn key_type_name() -> String {
+        format!("&{}", T::key_type_name())
+    }
+}
+
+/// Algorithm used to hash the serialized message in the cache key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyHasher {
+    /// 64-bit XXH3, fast non-cryptographic hash.
+    #[cfg(feature = "xxh3")]
+    Xxh3,
+    /// SHA-256 cryptographic hash.
+    #[cfg(feature = "sha2")]
+    Sha256,
+    /// BLAKE3 cryptographic hash.
+    #[cfg(feature = "blake3")]
+    Blake3,
+}
+
+impl KeyHasher {
+    /// Returns lowercase hex encoded hash of the data.
+    #[cfg_attr(
+        not(any(feature = "xxh3", feature = "sha2", feature = "blake3")),
+        allow(unused_variables)
+    )]
+    pub fn hash(&self, data: &[u8]) -> String {
+        match *self {
+            #[cfg(feature = "xxh3")]
+            KeyHasher::Xxh3 => format!("{:016x}", xxhash_rust::xxh3::xxh3_64(data)),
+            #[cfg(feature = "sha2")]
+            KeyHasher::Sha256 => {
+                use sha2::Digest;
+                to_hex(&sha2::Sha256::digest(data))
+            }
+            #[cfg(feature = "blake3")]
+            KeyHasher::Blake3 => to_hex(blake3::hash(data).as_bytes()),
+        }
+    }
+}
+
+#[cfg(any(feature = "sha2", feature = "blake3"))]
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Serialize, Hash)]
+    enum Filter {
+        Equal(String),
+        Range { from: i32, to: i32 },
+    }
+
+    #[test]
+    fn test_json_encoder_sorts_map_keys() {
+        let mut filters = HashMap::new();
+        filters.insert("b", Filter::Range { from: 1, to: 2 });
+        filters.insert("a", Filter::Equal("x".to_owned()));
+        filters.insert("c", Filter::Equal("y".to_owned()));
+        assert_eq!(
+            JsonEncoder::encode(&filters).unwrap(),
+            r#"{"a":{"Equal":"x"},"b":{"Range":{"from":1,"to":2}},"c":{"Equal":"y"}}"#
+        );
+    }
+
+    #[test]
+    fn test_json_encoder_field() {
+        assert_eq!(
+            <JsonEncoder as KeyEncoder<_>>::encode_field("ids", &vec![1, 2]).unwrap(),
+            "ids=[1,2]"
+        );
+    }
+
+    #[test]
+    fn test_hash_encoder() {
+        let first = HashEncoder::encode(&Filter::Range { from: 1, to: 2 }).unwrap();
+        let second = HashEncoder::encode(&Filter::Range { from: 1, to: 3 }).unwrap();
+        assert_eq!(first.len(), 16);
+        assert_eq!(
+            first,
+            HashEncoder::encode(&Filter::Range { from: 1, to: 2 }).unwrap()
+        );
+        assert_ne!(first, second);
+        // The hash is stable between Rust releases.
+        assert_eq!(HashEncoder::encode(&42u32).unwrap(), "ecc04cf36ccd5357");
+    }
+
+    #[cfg(feature = "xxh3")]
+    #[test]
+    fn test_xxh3() {
+        assert_eq!(KeyHasher::Xxh3.hash(b""), "2d06800538d394c2");
+    }
+
+    #[cfg(feature = "sha2")]
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            KeyHasher::Sha256.hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn test_blake3() {
+        assert_eq!(
+            KeyHasher::Blake3.hash(b""),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+}
@@ -28,6 +28,7 @@
 //! ## Restrictions
 //! Default cache key implementation based on serde_qs crate
 //! and have some [restrictions](https://docs.rs/serde_qs/latest/serde_qs/#supported-types).
+//! Other [key encoders](crate::key::KeyEncoder) can be used for types which aren't supported.
 //!
 //! ## Example
 //! First of all, you should derive [Cacheable] trait for your struct or enum:
@@ -66,8 +67,10 @@
 pub mod cache;
 pub mod dev;
 pub mod error;
+pub mod key;
 #[cfg(feature = "metrics")]
 pub mod metrics;
+pub mod outcome;
 pub mod response;
 pub mod runtime;
 pub mod settings;
@@ -77,16 +80,32 @@ pub mod value;
 
 pub use cache::Cacheable;
 pub use error::CacheError;
-pub use response::{CachePolicy, CacheableResponse};
-pub use value::{CacheState, CachedValue};
+#[cfg(feature = "derive")]
+pub use key::QueryEncoder;
+pub use key::{HashEncoder, JsonEncoder, KeyEncoder, KeyHasher, KeyTypeName};
+pub use outcome::{CacheOutcome, CacheStatus};
+pub use response::{CachePolicy, CacheableResponse, CachedRef};
+pub use settings::SettingsOverride;
+pub use value::{CacheState, CachedRecord, CachedValue};
 
 #[cfg(feature = "derive")]
 #[doc(hidden)]
-pub use serde_qs as hitbox_serializer;
+pub mod hitbox_serializer {
+    //! Items used by the code generated with derive macros.
+    pub use crate::cache::DEFAULT_CACHE_TTL;
+    pub use crate::key::{KeyEncoder, KeyTypeName, QueryEncoder};
+    pub use crate::response::CachedRef;
+    pub use crate::settings::SettingsOverride;
+    pub use serde::{self, Deserialize, Serialize};
+    pub use serde_qs::*;
+}
 
 /// The `hitbox` prelude.
 pub mod prelude {
     #[cfg(feature = "derive")]
-    pub use crate::hitbox_serializer;
-    pub use crate::{CacheError, Cacheable, CacheableResponse};
+    pub use crate::{hitbox_serializer, QueryEncoder};
+    pub use crate::{
+        CacheError, CachePolicy, Cacheable, CacheableResponse, CachedRef, HashEncoder, JsonEncoder,
+        KeyEncoder, KeyHasher, KeyTypeName, SettingsOverride,
+    };
 }
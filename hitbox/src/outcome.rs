@@ -0,0 +1,56 @@
+//! Result of cache interaction with metadata about the way it was obtained.
+use chrono::{DateTime, Utc};
+
+/// Describes the path the FSM has taken to obtain the result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheStatus {
+    /// Cache is disabled, value polled from upstream.
+    Bypass,
+    /// Actual value retrieved from cache.
+    Hit,
+    /// Stale value retrieved from cache.
+    Stale,
+    /// Stale value retrieved from cache because upstream polling failed.
+    StaleIfError,
+    /// Cached value is missing, value polled from upstream.
+    Miss,
+    /// Cached value is stale, fresh value polled from upstream.
+    Expired,
+    /// Cache backend error occurred, value polled from upstream.
+    Error,
+}
+
+impl CacheStatus {
+    /// Returns `true` if the value was retrieved from cache.
+    pub fn is_cached(&self) -> bool {
+        matches!(
+            self,
+            CacheStatus::Hit | CacheStatus::Stale | CacheStatus::StaleIfError
+        )
+    }
+}
+
+/// Value with cache metadata.
+#[derive(Debug)]
+pub struct CacheOutcome<T> {
+    /// Returned value.
+    pub result: T,
+    /// The path the FSM has taken to obtain the result.
+    pub status: CacheStatus,
+    /// UTC time when the value was stored into cache, if the value was retrieved from cache.
+    pub created: Option<DateTime<Utc>>,
+    /// UTC time after which the value is stale, if the value was retrieved from cache.
+    pub expired: Option<DateTime<Utc>>,
+}
+
+impl<T> CacheOutcome<T> {
+    /// Returns age of the cached value.
+    pub fn age(&self) -> Option<chrono::Duration> {
+        self.created.map(|created| Utc::now() - created)
+    }
+
+    /// Returns inner value.
+    pub fn into_inner(self) -> T {
+        self.result
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! For more detailed information and examples please see [CacheableResponse
 //! documentation](trait.CacheableResponse.html).
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{de::DeserializeOwned, ser::Error, Serialize, Serializer};
+use serde_json::value::RawValue;
 
 #[cfg(feature = "derive")]
 pub use hitbox_derive::CacheableResponse;
@@ -15,6 +16,39 @@ pub enum CachePolicy<T, U> {
     NonCacheable(U),
 }
 
+/// Borrowed data of the cacheable value, serialized the same way as its `Cached` type.
+///
+/// It allows to return data which doesn't exist as `&Cached`, e.g. a borrowed view of a
+/// generated `Cached` enum.
+pub struct CachedRef<'a>(Box<dyn SerializeJson + 'a>);
+
+impl<'a> CachedRef<'a> {
+    /// Wraps borrowed data which serializes the same way as `Cached` type.
+    pub fn new<T: Serialize + 'a>(data: T) -> Self {
+        Self(Box::new(data))
+    }
+}
+
+impl Serialize for CachedRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0
+            .to_json()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// Object safe serialization of borrowed data into JSON.
+trait SerializeJson {
+    fn to_json(&self) -> serde_json::Result<Box<RawValue>>;
+}
+
+impl<T: Serialize> SerializeJson for T {
+    fn to_json(&self) -> serde_json::Result<Box<RawValue>> {
+        serde_json::value::to_raw_value(self)
+    }
+}
+
 /// Thit is one of the basic trait which determines should data store in cache backend or not.
 ///
 /// For primitive types and for user-defined types (with derive macro)
@@ -27,11 +61,23 @@ pub enum CachePolicy<T, U> {
 /// `NonCacheable` by default.
 ///
 /// ## User defined types:
-/// If you want decribe custom caching rules for your own types (for example Enum) you should
-/// implement `CacheableResponse` for that type:
+/// If you want decribe custom caching rules for your own enums you can mark their variants
+/// with `cacheable` and `non_cacheable` attributes of the derive macro:
+///
+/// ```rust,ignore
+/// use hitbox::prelude::*;
+///
+/// #[derive(CacheableResponse)]
+/// enum HttpResponse {
+///     #[cacheable]
+///     Ok(String),
+///     Unauthorized(i32),
+/// }
+/// ```
+/// The derive macro generates the following implementation:
 ///
 /// ```rust
-/// use hitbox::{CacheableResponse, CachePolicy};
+/// use hitbox::{CacheableResponse, CachePolicy, CachedRef};
 ///
 /// enum HttpResponse {
 ///     Ok(String),
@@ -40,9 +86,9 @@ pub enum CachePolicy<T, U> {
 ///
 /// impl CacheableResponse for HttpResponse {
 ///     type Cached = String;
-///     fn cache_policy(&self) -> CachePolicy<&Self::Cached, ()> {
+///     fn cache_policy(&self) -> CachePolicy<CachedRef<'_>, ()> {
 ///         match self {
-///             HttpResponse::Ok(body) => CachePolicy::Cacheable(body),
+///             HttpResponse::Ok(body) => CachePolicy::Cacheable(CachedRef::new(body)),
 ///             _ => CachePolicy::NonCacheable(()),
 ///         }
 ///     }
@@ -59,6 +105,9 @@ pub enum CachePolicy<T, U> {
 /// ```
 /// In that case only `HttpResponse::Ok` variant will be saved into the cache backend.
 /// And all `String`s from the cache backend will be treated as `HttpReponse::Ok(String)` variant.
+///
+/// If several variants are cacheable, the derive macro generates `{Name}Cached` enum
+/// with the same cacheable variants and uses it as `Cached` type.
 pub trait CacheableResponse
 where
     Self: Sized,
@@ -67,11 +116,27 @@ where
     /// Describes what type will be stored into the cache backend.
     type Cached;
     /// Returns cache policy for current type with borrowed data.
-    fn cache_policy(&self) -> CachePolicy<&Self::Cached, ()>;
+    ///
+    /// Borrowed data must be serialized the same way as `Cached` type.
+    fn cache_policy(&self) -> CachePolicy<CachedRef<'_>, ()>;
     /// Returns cache policy for current type with owned data.
     fn into_cache_policy(self) -> CachePolicy<Self::Cached, Self>;
     /// Describes how previously cached data will be transformed into the original type.
     fn from_cached(cached: Self::Cached) -> Self;
+    /// Returns the schema version of `Cached` type stored with cached data.
+    ///
+    /// Bump it when `Cached` type changes its shape or meaning. Data cached with another
+    /// schema version is treated as missing and overwritten, unless `migrate_cached`
+    /// converts it into the current `Cached` type.
+    fn cache_schema_version() -> u32 {
+        0
+    }
+    /// Converts data cached with another schema version into the current `Cached` type.
+    ///
+    /// Returns `None` by default, so such data is treated as missing.
+    fn migrate_cached(_schema_version: u32, _data: serde_json::Value) -> Option<Self::Cached> {
+        None
+    }
 }
 
 // There are several CacheableResponse implementations for the most common types.
@@ -92,9 +157,9 @@ where
     fn from_cached(cached: Self::Cached) -> Self {
         Ok(cached)
     }
-    fn cache_policy(&self) -> CachePolicy<&Self::Cached, ()> {
+    fn cache_policy(&self) -> CachePolicy<CachedRef<'_>, ()> {
         match self {
-            Ok(value) => CachePolicy::Cacheable(value),
+            Ok(value) => CachePolicy::Cacheable(CachedRef::new(value)),
             Err(_) => CachePolicy::NonCacheable(()),
         }
     }
@@ -116,9 +181,9 @@ where
     fn from_cached(cached: Self::Cached) -> Self {
         Some(cached)
     }
-    fn cache_policy(&self) -> CachePolicy<&Self::Cached, ()> {
+    fn cache_policy(&self) -> CachePolicy<CachedRef<'_>, ()> {
         match self {
-            Some(value) => CachePolicy::Cacheable(value),
+            Some(value) => CachePolicy::Cacheable(CachedRef::new(value)),
             None => CachePolicy::NonCacheable(()),
         }
     }
@@ -135,8 +200,8 @@ macro_rules! CACHEABLE_RESPONSE_IMPL {
             fn from_cached(cached: Self::Cached) -> Self {
                 cached
             }
-            fn cache_policy(&self) -> CachePolicy<&Self::Cached, ()> {
-                CachePolicy::Cacheable(self)
+            fn cache_policy(&self) -> CachePolicy<CachedRef<'_>, ()> {
+                CachePolicy::Cacheable(CachedRef::new(self))
             }
         }
     };
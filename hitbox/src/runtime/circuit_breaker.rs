@@ -0,0 +1,161 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+/// Error returned instead of cache backend operation skipped by an open [`CircuitBreaker`].
+#[derive(Debug, Error)]
+#[error("Circuit breaker is open, cache backend operation skipped")]
+pub struct CircuitOpenError;
+
+/// Current state of [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CircuitState {
+    /// Cache backend is healthy, all operations are allowed.
+    Closed,
+    /// Cache backend is unhealthy, all operations are skipped.
+    Open,
+    /// Reset timeout elapsed, one trial operation is allowed.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+enum BreakerState {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { since: Instant },
+}
+
+/// Circuit breaker for cache backend interactions.
+///
+/// After `failure_threshold` consecutive connection errors the breaker opens
+/// and all cache operations are skipped until `reset_timeout` elapses.
+/// Then one trial operation is allowed: success closes the breaker,
+/// failure opens it again.
+///
+/// Clones share the same state, so one breaker instance can serve many requests.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failure_threshold", &self.failure_threshold)
+            .field("reset_timeout", &self.reset_timeout)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates new closed circuit breaker.
+    pub fn new(failure_threshold: u32, reset_timeout: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            reset_timeout,
+            state: Arc::new(Mutex::new(BreakerState::Closed { failures: 0 })),
+        }
+    }
+
+    /// Returns current breaker state.
+    pub fn state(&self) -> CircuitState {
+        match *self.lock() {
+            BreakerState::Closed { .. } => CircuitState::Closed,
+            BreakerState::Open { .. } => CircuitState::Open,
+            BreakerState::HalfOpen { .. } => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Checks if cache operation is allowed.
+    ///
+    /// When the reset timeout of an open breaker elapses this method
+    /// moves breaker into half-open state and allows exactly one operation.
+    pub fn is_available(&self) -> bool {
+        let mut state = self.lock();
+        let now = Instant::now();
+        match *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } if now >= until => {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            BreakerState::Open { .. } => false,
+            // Trial operation result was lost (for example, request was cancelled).
+            BreakerState::HalfOpen { since } if now >= since + self.reset_timeout => {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            BreakerState::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Records successful cache operation and closes the breaker.
+    pub fn record_success(&self) {
+        *self.lock() = BreakerState::Closed { failures: 0 };
+    }
+
+    /// Records failed cache operation.
+    pub fn record_failure(&self) {
+        let mut state = self.lock();
+        let failures = match *state {
+            BreakerState::Closed { failures } => failures + 1,
+            BreakerState::HalfOpen { .. } => self.failure_threshold,
+            BreakerState::Open { .. } => return,
+        };
+        *state = if failures >= self.failure_threshold {
+            BreakerState::Open {
+                until: Instant::now() + self.reset_timeout,
+            }
+        } else {
+            BreakerState::Closed { failures }
+        };
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        assert!(breaker.is_available());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.is_available());
+    }
+
+    #[test]
+    fn test_breaker_success_resets_failures() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_breaker_half_open_allows_one_trial() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(0));
+        breaker.record_failure();
+        assert!(breaker.is_available());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.is_available());
+        breaker.record_success();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
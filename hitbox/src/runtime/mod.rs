@@ -1,4 +1,8 @@
 //! Cache backend runtime agnostic interaction.
 mod adapter;
+mod circuit_breaker;
+mod retry;
 
 pub use adapter::{AdapterResult, EvictionPolicy, RuntimeAdapter, TtlSettings};
+pub use circuit_breaker::{CircuitBreaker, CircuitOpenError, CircuitState};
+pub use retry::RetryPolicy;
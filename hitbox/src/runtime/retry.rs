@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+/// Upstream polling retry policy.
+///
+/// Delay before each next retry is doubled, starting from `backoff`
+/// and limited by `max_backoff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of additional upstream polling attempts.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub backoff: Duration,
+    /// Upper bound of delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Creates retry policy with exponential backoff.
+    pub fn new(max_retries: u32, backoff: Duration) -> Self {
+        Self {
+            max_retries,
+            backoff,
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+
+    /// Returns delay before retry with passed number (starting from zero).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(0, Duration::from_millis(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_exponential() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(100));
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_delay_limited() {
+        let policy = RetryPolicy::new(64, Duration::from_secs(1));
+        assert_eq!(policy.delay(63), policy.max_backoff);
+    }
+}
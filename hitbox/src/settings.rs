@@ -20,6 +20,78 @@ pub struct CacheSettings {
     pub lock: Status,
 }
 
+impl From<bool> for Status {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            Status::Enabled
+        } else {
+            Status::Disabled
+        }
+    }
+}
+
+impl CacheSettings {
+    /// Returns settings with overridden values replaced.
+    pub fn apply(&self, overrides: &SettingsOverride) -> CacheSettings {
+        CacheSettings {
+            cache: overrides
+                .cache
+                .clone()
+                .unwrap_or_else(|| self.cache.clone()),
+            stale: overrides
+                .stale
+                .clone()
+                .unwrap_or_else(|| self.stale.clone()),
+            lock: self.lock.clone(),
+        }
+    }
+}
+
+/// Overrides of [CacheSettings] for a message type or a single query.
+///
+/// Settings which are `None` are taken from the cache actor.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsOverride {
+    /// Enable or disable cache at all.
+    pub cache: Option<Status>,
+    /// Enable or disable cache stale mechanics.
+    pub stale: Option<Status>,
+}
+
+impl SettingsOverride {
+    /// Enable interaction with cache backend.
+    pub fn enable(mut self) -> Self {
+        self.cache = Some(Status::Enabled);
+        self
+    }
+
+    /// Disable interaction with cache backend, the upstream is polled directly.
+    pub fn disable(mut self) -> Self {
+        self.cache = Some(Status::Disabled);
+        self
+    }
+
+    /// Enable cache stale mechanics.
+    pub fn with_stale(mut self) -> Self {
+        self.stale = Some(Status::Enabled);
+        self
+    }
+
+    /// Disable cache stale mechanics.
+    pub fn without_stale(mut self) -> Self {
+        self.stale = Some(Status::Disabled);
+        self
+    }
+
+    /// Combines two overrides, values of `other` take precedence.
+    pub fn merge(self, other: SettingsOverride) -> SettingsOverride {
+        SettingsOverride {
+            cache: other.cache.or(self.cache),
+            stale: other.stale.or(self.stale),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum InitialCacheSettings {
     Disabled,
@@ -59,3 +131,33 @@ impl From<CacheSettings> for InitialCacheSettings {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_overrides() {
+        let settings = CacheSettings {
+            cache: Status::Enabled,
+            stale: Status::Enabled,
+            lock: Status::Disabled,
+        };
+        let overrides = SettingsOverride::default()
+            .without_stale()
+            .merge(SettingsOverride::default().with_stale());
+        assert_eq!(
+            InitialCacheSettings::from(settings.apply(&overrides)),
+            InitialCacheSettings::Stale
+        );
+        let overrides = overrides.merge(SettingsOverride::default().disable());
+        assert_eq!(
+            InitialCacheSettings::from(settings.apply(&overrides)),
+            InitialCacheSettings::Disabled
+        );
+        assert_eq!(
+            InitialCacheSettings::from(settings.apply(&SettingsOverride::default())),
+            InitialCacheSettings::Stale
+        );
+    }
+}
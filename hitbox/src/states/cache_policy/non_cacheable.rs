@@ -3,6 +3,7 @@ use std::fmt;
 use tracing::{instrument, trace};
 
 use crate::states::finish::Finish;
+use crate::CacheStatus;
 
 /// This state is a non cacheable variant from [CachePolicyChecked](enum.CachePolicyChecked.html).
 pub struct CachePolicyNonCacheable<T> {
@@ -22,8 +23,6 @@ impl<T> CachePolicyNonCacheable<T> {
     /// If the value cannot be cached, we have to return it.
     pub fn finish(self) -> Finish<T> {
         trace!("Finish");
-        Finish {
-            result: Ok(self.result),
-        }
+        Finish::new(Ok(self.result), CacheStatus::Miss)
     }
 }
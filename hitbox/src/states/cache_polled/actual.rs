@@ -6,6 +6,7 @@ use tracing::{instrument, trace};
 use crate::response::CacheableResponse;
 use crate::runtime::RuntimeAdapter;
 use crate::states::finish::Finish;
+use crate::CacheStatus;
 use crate::CachedValue;
 
 /// This state is a variant with actual data from [CachePolled](enum.CachePolled.html).
@@ -40,8 +41,6 @@ where
     /// We have to return actual data.
     pub fn finish(self) -> Finish<T> {
         trace!("Finish");
-        Finish {
-            result: Ok(self.result.into_inner()),
-        }
+        Finish::from_cached(self.result, CacheStatus::Hit)
     }
 }
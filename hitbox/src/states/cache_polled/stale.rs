@@ -8,6 +8,7 @@ use crate::states::finish::Finish;
 use crate::states::upstream_polled::{
     UpstreamPolledErrorStaleRetrieved, UpstreamPolledStaleRetrieved, UpstreamPolledSuccessful,
 };
+use crate::CacheStatus;
 use crate::CachedValue;
 use std::fmt;
 
@@ -58,7 +59,7 @@ where
                 warn!("Upstream error {}", error);
                 UpstreamPolledStaleRetrieved::Error(UpstreamPolledErrorStaleRetrieved {
                     error,
-                    result: self.result.into_inner(),
+                    result: self.result,
                 })
             }
         }
@@ -68,8 +69,6 @@ where
     /// Return data with Finish state.
     pub fn finish(self) -> Finish<T> {
         trace!("Finish");
-        Finish {
-            result: Ok(self.result.into_inner()),
-        }
+        Finish::from_cached(self.result, CacheStatus::Stale)
     }
 }
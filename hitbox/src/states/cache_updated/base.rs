@@ -5,18 +5,23 @@ use tracing::{instrument, trace};
 
 use crate::runtime::RuntimeAdapter;
 use crate::states::finish::Finish;
+use crate::CacheStatus;
 
 /// State after transition `update_cache`.
 ///
 /// The transition to this state doesn't depend on the success of the cache update operation.
+/// This state can be reached from
+/// [CachePolicyCacheable](crate::states::cache_policy::CachePolicyCacheable) state only,
+/// so non cacheable values are never stored in the cache.
 pub struct CacheUpdated<A, T>
 where
     A: RuntimeAdapter,
 {
     /// Runtime adapter.
-    pub adapter: A,
-    /// Value retrieved from cache or from upstream.
-    pub result: T,
+    #[allow(dead_code)]
+    pub(crate) adapter: A,
+    /// Value retrieved from upstream.
+    pub(crate) result: T,
 }
 
 /// Required `Debug` implementation to use `instrument` macro.
@@ -38,8 +43,6 @@ where
     /// We have to return actual data.
     pub fn finish(self) -> Finish<T> {
         trace!("Finish");
-        Finish {
-            result: Ok(self.result),
-        }
+        Finish::new(Ok(self.result), CacheStatus::Miss)
     }
 }
@@ -1,13 +1,21 @@
 use std::fmt;
 
+use chrono::{DateTime, Utc};
 use tracing::{instrument, trace};
 
-use crate::CacheError;
+use crate::response::CacheableResponse;
+use crate::{CacheError, CacheOutcome, CacheStatus, CachedValue};
 
 /// Finite state.
 pub struct Finish<T> {
     /// The field represents the return value.
     pub result: Result<T, CacheError>,
+    /// The path the FSM has taken to obtain the value.
+    pub status: CacheStatus,
+    /// UTC time when the value was stored into cache, if the value was retrieved from cache.
+    pub created: Option<DateTime<Utc>>,
+    /// UTC time after which the value is stale, if the value was retrieved from cache.
+    pub expired: Option<DateTime<Utc>>,
 }
 
 /// Required `Debug` implementation to use `instrument` macro.
@@ -18,10 +26,51 @@ impl<T> fmt::Debug for Finish<T> {
 }
 
 impl<T> Finish<T> {
+    /// Create Finish state with the value which wasn't retrieved from cache.
+    pub fn new(result: Result<T, CacheError>, status: CacheStatus) -> Self {
+        Self {
+            result,
+            status,
+            created: None,
+            expired: None,
+        }
+    }
+
+    /// Create Finish state with the value retrieved from cache.
+    pub fn from_cached(value: CachedValue<T>, status: CacheStatus) -> Self
+    where
+        T: CacheableResponse,
+    {
+        Self {
+            created: value.created(),
+            expired: Some(value.expired()),
+            result: Ok(value.into_inner()),
+            status,
+        }
+    }
+
+    /// Replace the path the FSM has taken to obtain the value.
+    pub fn with_status(self, status: CacheStatus) -> Self {
+        Self { status, ..self }
+    }
+
     #[instrument]
     /// Return inner value `result`.
     pub fn result(self) -> Result<T, CacheError> {
         trace!("Result");
         self.result
     }
+
+    #[instrument]
+    /// Return inner value `result` with cache metadata.
+    pub fn outcome(self) -> Result<CacheOutcome<T>, CacheError> {
+        trace!("Outcome");
+        let (status, created, expired) = (self.status, self.created, self.expired);
+        self.result.map(|result| CacheOutcome {
+            result,
+            status,
+            created,
+            expired,
+        })
+    }
 }
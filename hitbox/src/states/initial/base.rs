@@ -8,11 +8,12 @@ use crate::settings::{CacheSettings, InitialCacheSettings};
 use crate::states::cache_polled::{
     CacheErrorOccurred, CacheMissed, CachePolled, CachePolledActual, CachePolledStale,
 };
+use crate::states::finish::Finish;
 use crate::states::upstream_polled::{
     UpstreamPolled, UpstreamPolledError, UpstreamPolledSuccessful,
 };
 use crate::transition_groups::{only_cache, stale, upstream};
-use crate::{CacheError, CacheState};
+use crate::{CacheError, CacheOutcome, CacheState};
 
 /// Initial state.
 pub struct Initial<A>
@@ -111,18 +112,36 @@ where
         }
     }
 
-    /// Run all transitions from Initial state to Result.
-    pub async fn transitions<T>(self) -> Result<T, CacheError>
+    /// Run all transitions from Initial state to Finish.
+    async fn finish<T>(self) -> Finish<T>
     where
         A: RuntimeAdapter<UpstreamResult = T>,
         T: CacheableResponse + fmt::Debug,
     {
         match self.settings {
-            InitialCacheSettings::Disabled => upstream::transition(self).await.result(),
-            InitialCacheSettings::Enabled => only_cache::transition(self).await.result(),
-            InitialCacheSettings::Stale => stale::transition(self).await.result(),
+            InitialCacheSettings::Disabled => upstream::transition(self).await,
+            InitialCacheSettings::Enabled => only_cache::transition(self).await,
+            InitialCacheSettings::Stale => stale::transition(self).await,
             InitialCacheSettings::Lock => unimplemented!(),
             InitialCacheSettings::StaleLock => unimplemented!(),
         }
     }
+
+    /// Run all transitions from Initial state to Result.
+    pub async fn transitions<T>(self) -> Result<T, CacheError>
+    where
+        A: RuntimeAdapter<UpstreamResult = T>,
+        T: CacheableResponse + fmt::Debug,
+    {
+        self.finish().await.result()
+    }
+
+    /// Run all transitions from Initial state to Result with cache metadata.
+    pub async fn transitions_with_outcome<T>(self) -> Result<CacheOutcome<T>, CacheError>
+    where
+        A: RuntimeAdapter<UpstreamResult = T>,
+        T: CacheableResponse + fmt::Debug,
+    {
+        self.finish().await.outcome()
+    }
 }
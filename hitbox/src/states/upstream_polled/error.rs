@@ -4,6 +4,7 @@ use tracing::{instrument, trace};
 
 use crate::states::finish::Finish;
 use crate::CacheError;
+use crate::CacheStatus;
 
 /// This state is a variant without data.
 pub struct UpstreamPolledError {
@@ -23,8 +24,6 @@ impl UpstreamPolledError {
     /// Upstream returns an error. FSM goes to Finish.
     pub fn finish<T>(self) -> Finish<T> {
         trace!("Finish");
-        Finish {
-            result: Err(self.error),
-        }
+        Finish::new(Err(self.error), CacheStatus::Bypass)
     }
 }
@@ -2,15 +2,16 @@ use std::fmt;
 
 use tracing::{instrument, trace};
 
+use crate::response::CacheableResponse;
 use crate::states::finish::Finish;
-use crate::CacheError;
+use crate::{CacheError, CacheStatus, CachedValue};
 
 /// Stale value was retrieved and poll upstream returned an error.
 pub struct UpstreamPolledErrorStaleRetrieved<T> {
     /// Returned error.
     pub error: CacheError,
     /// Stale value retrieved from cache.
-    pub result: T,
+    pub result: CachedValue<T>,
 }
 
 /// Required `Debug` implementation to use `instrument` macro.
@@ -20,13 +21,14 @@ impl<T> fmt::Debug for UpstreamPolledErrorStaleRetrieved<T> {
     }
 }
 
-impl<T> UpstreamPolledErrorStaleRetrieved<T> {
+impl<T> UpstreamPolledErrorStaleRetrieved<T>
+where
+    T: CacheableResponse,
+{
     #[instrument]
     /// Upstream returns an error. FSM goes to Finish.
     pub fn finish(self) -> Finish<T> {
         trace!("Finish");
-        Finish {
-            result: Ok(self.result),
-        }
+        Finish::from_cached(self.result, CacheStatus::StaleIfError)
     }
 }
@@ -1,16 +1,15 @@
 use std::fmt;
 use std::fmt::Debug;
 
-use tracing::{instrument, trace, warn};
+use tracing::{instrument, trace};
 
 use crate::response::{CachePolicy, CacheableResponse};
 use crate::runtime::RuntimeAdapter;
 use crate::states::cache_policy::{
     CachePolicyCacheable, CachePolicyChecked, CachePolicyNonCacheable,
 };
-use crate::states::cache_updated::CacheUpdated;
 use crate::states::finish::Finish;
-use crate::CachedValue;
+use crate::CacheStatus;
 
 /// Upstream returns value.
 pub struct UpstreamPolledSuccessful<A, T>
@@ -44,23 +43,26 @@ where
     /// Return retrieved value.
     pub fn finish(self) -> Finish<T> {
         trace!("Finish");
-        Finish {
-            result: Ok(self.result),
-        }
+        Finish::new(Ok(self.result), CacheStatus::Bypass)
     }
 
     #[instrument]
     /// Check if the value can be cached.
+    ///
+    /// It's the only way to store the value in the cache,
+    /// [CacheUpdated](crate::states::cache_updated::CacheUpdated) state can be reached
+    /// from [CachePolicyCacheable] state only.
     pub fn check_cache_policy(self) -> CachePolicyChecked<A, T> {
-        match self.result.cache_policy() {
-            CachePolicy::Cacheable(_) => {
+        let cacheable = matches!(self.result.cache_policy(), CachePolicy::Cacheable(_));
+        match cacheable {
+            true => {
                 trace!("CachePolicyCacheable");
                 CachePolicyChecked::Cacheable(CachePolicyCacheable {
                     result: self.result,
                     adapter: self.adapter,
                 })
             }
-            CachePolicy::NonCacheable(_) => {
+            false => {
                 trace!("CachePolicyNonCacheable");
                 CachePolicyChecked::NonCacheable(CachePolicyNonCacheable {
                     result: self.result,
@@ -68,19 +70,4 @@ where
             }
         }
     }
-
-    #[instrument]
-    /// Store the value in the cache.
-    pub async fn update_cache(self) -> CacheUpdated<A, T> {
-        let cached_value = CachedValue::from((self.result, self.adapter.eviction_settings()));
-        let cache_update_result = self.adapter.update_cache(&cached_value).await;
-        if let Err(error) = cache_update_result {
-            warn!("Updating cache error: {}", error.to_string())
-        };
-        trace!("CacheUpdated");
-        CacheUpdated {
-            adapter: self.adapter,
-            result: cached_value.into_inner(),
-        }
-    }
 }
@@ -5,6 +5,7 @@ use crate::states::cache_polled::CachePolled;
 use crate::states::finish::Finish;
 use crate::states::initial::Initial;
 use crate::states::upstream_polled::{UpstreamPolled, UpstreamPolledStaleRetrieved};
+use crate::CacheStatus;
 use std::fmt::Debug;
 
 /// Transition for `InitialCacheSettings::CacheStale` option.
@@ -20,7 +21,8 @@ where
             UpstreamPolledStaleRetrieved::Successful(state) => match state.check_cache_policy() {
                 CachePolicyChecked::Cacheable(state) => state.update_cache().await.finish(),
                 CachePolicyChecked::NonCacheable(state) => state.finish(),
-            },
+            }
+            .with_status(CacheStatus::Expired),
             UpstreamPolledStaleRetrieved::Error(state) => state.finish(),
         },
         CachePolled::Miss(state) => match state.poll_upstream().await {
@@ -29,10 +31,15 @@ where
                 CachePolicyChecked::NonCacheable(state) => state.finish(),
             },
             UpstreamPolled::Error(error) => error.finish(),
-        },
+        }
+        .with_status(CacheStatus::Miss),
         CachePolled::Error(state) => match state.poll_upstream().await {
-            UpstreamPolled::Successful(state) => state.update_cache().await.finish(),
+            UpstreamPolled::Successful(state) => match state.check_cache_policy() {
+                CachePolicyChecked::Cacheable(state) => state.update_cache().await.finish(),
+                CachePolicyChecked::NonCacheable(state) => state.finish(),
+            },
             UpstreamPolled::Error(error) => error.finish(),
-        },
+        }
+        .with_status(CacheStatus::Error),
     }
 }
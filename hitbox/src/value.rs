@@ -3,15 +3,21 @@ use crate::runtime::EvictionPolicy;
 use crate::{CacheError, CachePolicy, CacheableResponse};
 use chrono::{DateTime, Utc};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::value::RawValue;
+use tracing::debug;
 
 /// This struct wraps and represents cached data.
 ///
 /// The expired field defines the UTC data expiration time.
 /// Used for detection of stale data.
+/// The created field defines the UTC time when data was stored.
+/// It's optional for compatibility with previously cached data.
 #[derive(Deserialize)]
 pub struct CachedValue<T> {
     data: T,
     expired: DateTime<Utc>,
+    #[serde(default)]
+    created: Option<DateTime<Utc>>,
 }
 
 impl<T> From<(T, EvictionPolicy)> for CachedValue<T> {
@@ -20,8 +26,13 @@ impl<T> From<(T, EvictionPolicy)> for CachedValue<T> {
         match eviction_policy {
             EvictionPolicy::Ttl(settings) => {
                 let duration = chrono::Duration::seconds(settings.stale_ttl as i64);
-                let expired = chrono::Utc::now() + duration;
-                Self { data, expired }
+                let created = chrono::Utc::now();
+                let expired = created + duration;
+                Self {
+                    data,
+                    expired,
+                    created: Some(created),
+                }
             }
         }
     }
@@ -34,6 +45,32 @@ where
 {
     data: &'a U,
     expired: DateTime<Utc>,
+    created: Option<DateTime<Utc>>,
+    schema_version: u32,
+}
+
+/// Cached data with unparsed payload, used to check the schema version before deserialization.
+///
+/// The payload is borrowed from the cached bytes, so it's parsed once into the target type.
+/// Data cached before schema versions were introduced has version 0.
+#[derive(Deserialize)]
+struct CachedEnvelope<'a> {
+    #[serde(borrow)]
+    data: &'a RawValue,
+    expired: DateTime<Utc>,
+    #[serde(default)]
+    created: Option<DateTime<Utc>>,
+    #[serde(default)]
+    schema_version: u32,
+}
+
+impl<'a> CachedEnvelope<'a> {
+    fn from_slice(key: &str, bytes: &'a [u8]) -> Result<Self, CacheError> {
+        serde_json::from_slice(bytes).map_err(|source| CacheError::DeserializeError {
+            key: key.to_owned(),
+            source,
+        })
+    }
 }
 
 impl<T> CachedValue<T>
@@ -43,7 +80,11 @@ where
 {
     /// Creates new CachedValue
     pub fn new(data: T, expired: DateTime<Utc>) -> Self {
-        Self { data, expired }
+        Self {
+            data,
+            expired,
+            created: None,
+        }
     }
 
     fn from_inner<U>(cached_data: CachedValue<U>) -> Self
@@ -53,18 +94,23 @@ where
         Self {
             data: T::from_cached(cached_data.data),
             expired: cached_data.expired,
+            created: cached_data.created,
         }
     }
 
     /// Serialize CachedValue into bytes.
+    ///
+    /// Returns [`CacheError::NonCacheableError`] if data is non cacheable.
     pub fn serialize(&self) -> Result<Vec<u8>, CacheError> {
         match self.data.cache_policy() {
             CachePolicy::Cacheable(cache_value) => serde_json::to_vec(&CachedInnerValue {
-                data: cache_value,
+                data: &cache_value,
                 expired: self.expired,
+                created: self.created,
+                schema_version: T::cache_schema_version(),
             })
-            .map_err(CacheError::from),
-            CachePolicy::NonCacheable(_) => Err(CacheError::DeserializeError),
+            .map_err(CacheError::SerializeError),
+            CachePolicy::NonCacheable(_) => Err(CacheError::NonCacheableError),
         }
     }
 
@@ -72,6 +118,63 @@ where
     pub fn into_inner(self) -> T {
         self.data
     }
+
+    /// Returns reference to cached data.
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns UTC time after which cached data is stale.
+    pub fn expired(&self) -> DateTime<Utc> {
+        self.expired
+    }
+
+    /// Returns UTC time when data was stored into cache, if it's known.
+    pub fn created(&self) -> Option<DateTime<Utc>> {
+        self.created
+    }
+}
+
+/// Cached data decoded without knowing its type, used for inspection of the cache contents.
+#[derive(Debug, Clone)]
+pub struct CachedRecord {
+    /// Cached data in JSON representation.
+    pub data: serde_json::Value,
+    /// UTC time after which cached data is stale.
+    pub expired: DateTime<Utc>,
+    /// UTC time when data was stored into cache, if it's known.
+    pub created: Option<DateTime<Utc>>,
+    /// Schema version of cached data, see [`CacheableResponse::cache_schema_version`].
+    pub schema_version: u32,
+}
+
+impl CachedRecord {
+    /// Deserialize bytes of [CachedValue] stored by `key`.
+    pub fn from_bytes(key: &str, bytes: &[u8]) -> Result<Self, CacheError> {
+        let envelope = CachedEnvelope::from_slice(key, bytes)?;
+        let data = serde_json::from_str(envelope.data.get()).map_err(|source| {
+            CacheError::DeserializeError {
+                key: key.to_owned(),
+                source,
+            }
+        })?;
+        Ok(Self {
+            data,
+            expired: envelope.expired,
+            created: envelope.created,
+            schema_version: envelope.schema_version,
+        })
+    }
+
+    /// Returns `true` if cached data is stale.
+    pub fn is_stale(&self) -> bool {
+        self.expired < Utc::now()
+    }
+
+    /// Returns age of cached data, if the creation time is known.
+    pub fn age(&self) -> Option<chrono::Duration> {
+        self.created.map(|created| Utc::now() - created)
+    }
 }
 
 /// Represents cuurent state of cached data.
@@ -89,12 +192,42 @@ where
     T: CacheableResponse<Cached = U>,
     U: DeserializeOwned + Serialize,
 {
-    /// Deserialize optional vector of bytes and check the actuality.
-    pub fn from_bytes(bytes: Option<&Vec<u8>>) -> Result<Self, crate::CacheError> {
-        let cached_data = bytes
-            .map(|bytes| serde_json::from_slice::<CachedValue<U>>(bytes))
-            .transpose()?;
-        Ok(Self::from(cached_data))
+    /// Deserialize optional vector of bytes stored by `key` and check the actuality.
+    ///
+    /// Data cached with another schema version is migrated by
+    /// [`CacheableResponse::migrate_cached`] or treated as missing.
+    /// Data of the current schema version which doesn't match `Cached` type
+    /// is treated as missing too, so it's overwritten by the fresh value.
+    pub fn from_bytes(key: &str, bytes: Option<&[u8]>) -> Result<Self, CacheError> {
+        let envelope = match bytes {
+            Some(bytes) => CachedEnvelope::from_slice(key, bytes)?,
+            None => return Ok(Self::Miss),
+        };
+        let schema_version = T::cache_schema_version();
+        let data = if envelope.schema_version == schema_version {
+            serde_json::from_str::<U>(envelope.data.get())
+                .map_err(|error| {
+                    debug!(
+                        "Cached data for key {} doesn't match schema version {}: {}",
+                        key, schema_version, error
+                    )
+                })
+                .ok()
+        } else {
+            debug!(
+                "Cached data for key {} has schema version {}, expected {}",
+                key, envelope.schema_version, schema_version
+            );
+            serde_json::from_str(envelope.data.get())
+                .ok()
+                .and_then(|data| T::migrate_cached(envelope.schema_version, data))
+        };
+        let (expired, created) = (envelope.expired, envelope.created);
+        Ok(Self::from(data.map(|data| CachedValue {
+            data,
+            expired,
+            created,
+        })))
     }
 }
 
@@ -1,4 +1,5 @@
 use hitbox::prelude::*;
+use hitbox::settings::Status;
 use serde::Serialize;
 
 #[derive(Cacheable, Serialize)]
@@ -125,6 +126,26 @@ fn test_macro_helpers_work() {
     );
 }
 
+const CACHE_ENABLED: bool = true;
+
+#[derive(Cacheable, Serialize)]
+#[cache_stale(false)]
+#[cache_enabled(CACHE_ENABLED)]
+struct SettingsMessage {
+    id: i32,
+}
+
+#[test]
+fn test_settings_override() {
+    let settings = SettingsMessage { id: 1 }.cache_settings();
+    assert!(matches!(settings.cache, Some(Status::Enabled)));
+    assert!(matches!(settings.stale, Some(Status::Disabled)));
+    assert!(MacroHelpersMessage { message_type: 1 }
+        .cache_settings()
+        .stale
+        .is_none());
+}
+
 #[derive(Cacheable, Serialize)]
 struct DefaultMessage {
     message_type: i32,
@@ -137,3 +158,466 @@ fn test_default_ttl_stale_ttl_version_work() {
     assert_eq!(message.cache_stale_ttl(), 55);
     assert_eq!(message.cache_version(), 0);
 }
+
+fn lowercase(value: &str) -> String {
+    value.to_lowercase()
+}
+
+mod formatters {
+    pub fn first_letter(value: &str) -> Option<char> {
+        value.chars().next()
+    }
+}
+
+#[derive(Cacheable)]
+#[allow(dead_code)]
+struct FieldAttributesMessage {
+    id: i32,
+    #[cache_key(skip)]
+    request_id: String,
+    #[cache_key(rename = "q")]
+    query: String,
+    #[cache_key(with = lowercase)]
+    alias: String,
+    #[cache_key(rename = "letter", with = "formatters::first_letter")]
+    name: String,
+    tags: Vec<i32>,
+    page: Option<i32>,
+}
+
+#[test]
+fn test_field_attributes() {
+    let message = FieldAttributesMessage {
+        id: 1,
+        request_id: "d1b2f7a3".to_owned(),
+        query: "find".to_owned(),
+        alias: "Alias".to_owned(),
+        name: "name".to_owned(),
+        tags: vec![1, 2],
+        page: None,
+    };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "FieldAttributesMessage::v0::id=1&q=find&alias=alias&letter=n&tags[0]=1&tags[1]=2"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_skipped_field_does_not_affect_key() {
+    let first = FieldAttributesMessage {
+        id: 1,
+        request_id: "first".to_owned(),
+        query: "find".to_owned(),
+        alias: "alias".to_owned(),
+        name: "name".to_owned(),
+        tags: vec![],
+        page: Some(2),
+    };
+    let second = FieldAttributesMessage {
+        request_id: "second".to_owned(),
+        ..first
+    };
+    assert_eq!(
+        second.cache_key().unwrap(),
+        "FieldAttributesMessage::v0::id=1&q=find&alias=alias&letter=n&page=2".to_string()
+    );
+}
+
+#[derive(Cacheable)]
+#[allow(dead_code)]
+struct AllSkippedMessage {
+    #[cache_key(skip)]
+    request_id: String,
+}
+
+#[test]
+fn test_all_fields_skipped() {
+    let message = AllSkippedMessage {
+        request_id: "d1b2f7a3".to_owned(),
+    };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "AllSkippedMessage::v0::".to_string()
+    );
+}
+
+const NAMESPACE: &str = "billing";
+
+#[derive(Cacheable, Serialize)]
+#[cache_prefix("invoice")]
+struct PrefixMessage {
+    id: i32,
+}
+
+#[test]
+fn test_cache_prefix() {
+    let message = PrefixMessage { id: 42 };
+    assert_eq!(message.cache_key_prefix(), "invoice".to_string());
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "invoice::v0::id=42".to_string()
+    );
+}
+
+#[derive(Cacheable, Serialize)]
+#[cache_namespace(NAMESPACE)]
+#[cache_prefix("invoice")]
+struct NamespaceMessage {
+    id: i32,
+}
+
+#[test]
+fn test_cache_namespace() {
+    let message = NamespaceMessage { id: 42 };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "billing::invoice::v0::id=42".to_string()
+    );
+}
+
+mod orders {
+    use hitbox::prelude::*;
+    use serde::Serialize;
+
+    #[derive(Cacheable, Serialize)]
+    #[cache_module_path]
+    pub struct Message {
+        pub id: i32,
+    }
+
+    #[derive(Cacheable, Serialize)]
+    #[cache_namespace("shop")]
+    #[cache_module_path]
+    pub struct NamespaceMessage {
+        pub id: i32,
+    }
+}
+
+#[test]
+fn test_cache_module_path() {
+    let message = orders::Message { id: 42 };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "cacheable_derive::orders::Message::v0::id=42".to_string()
+    );
+    assert_ne!(
+        message.cache_key().unwrap(),
+        Message {
+            id: 42,
+            alias: String::new()
+        }
+        .cache_key()
+        .unwrap()
+    );
+    let message = orders::NamespaceMessage { id: 42 };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "shop::cacheable_derive::orders::NamespaceMessage::v0::id=42".to_string()
+    );
+}
+
+#[derive(Cacheable, Serialize)]
+struct GenericMessage<T> {
+    id: T,
+}
+
+#[test]
+fn test_generic_message() {
+    let message = GenericMessage { id: 42 };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "GenericMessage<i32>::v0::id=42".to_string()
+    );
+    let message = GenericMessage { id: 42u8 };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "GenericMessage<u8>::v0::id=42".to_string()
+    );
+}
+
+#[derive(Serialize)]
+struct UserId(i32);
+
+impl KeyTypeName for UserId {
+    fn key_type_name() -> String {
+        "user".to_owned()
+    }
+}
+
+#[test]
+fn test_generic_message_custom_type_name() {
+    let message = GenericMessage { id: UserId(42) };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "GenericMessage<user>::v0::id=42".to_string()
+    );
+}
+
+#[derive(Cacheable)]
+#[allow(dead_code)]
+struct GenericFieldsMessage<'a, T, const N: usize> {
+    name: &'a str,
+    #[cache_key(skip)]
+    request_id: T,
+}
+
+#[test]
+fn test_generic_fields_message() {
+    let message = GenericFieldsMessage::<(), 10> {
+        name: "name",
+        request_id: (),
+    };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "GenericFieldsMessage<(), 10>::v0::name=name".to_string()
+    );
+}
+
+#[derive(Cacheable)]
+#[allow(dead_code)]
+enum Command {
+    Get {
+        id: i32,
+        #[cache_key(skip)]
+        request_id: String,
+    },
+    Find(String, #[cache_key(rename = "page")] i32),
+    Reset,
+}
+
+#[test]
+fn test_enum_message() {
+    let message = Command::Get {
+        id: 42,
+        request_id: "d1b2f7a3".to_owned(),
+    };
+    assert_eq!(message.cache_key_prefix(), "Command::Get".to_string());
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "Command::Get::v0::id=42".to_string()
+    );
+    let message = Command::Find("query".to_owned(), 2);
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "Command::Find::v0::0=query&page=2".to_string()
+    );
+    let message = Command::Reset;
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "Command::Reset::v0::".to_string()
+    );
+}
+
+#[derive(Cacheable)]
+#[cache_prefix("lookup")]
+#[cache_version(2)]
+enum GenericCommand<T> {
+    ById(T),
+}
+
+#[test]
+fn test_generic_enum_message() {
+    let message = GenericCommand::ById(42);
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "lookup<i32>::ById::v2::0=42".to_string()
+    );
+}
+
+const CONFIG_TTL: u32 = 120;
+
+mod config {
+    pub const STALE_TTL: u32 = 100;
+}
+
+#[derive(Cacheable, Serialize)]
+#[cache_ttl(CONFIG_TTL)]
+#[cache_stale_ttl(config::STALE_TTL)]
+#[cache_version(CONFIG_TTL / 60)]
+struct ConstAttributesMessage {
+    id: i32,
+}
+
+#[test]
+fn test_const_attributes() {
+    let message = ConstAttributesMessage { id: 1 };
+    assert_eq!(message.cache_ttl(), 120);
+    assert_eq!(message.cache_stale_ttl(), 100);
+    assert_eq!(message.cache_version(), 2);
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "ConstAttributesMessage::v2::id=1".to_string()
+    );
+}
+
+#[cfg(feature = "sha2")]
+#[derive(Cacheable, Serialize)]
+#[cache_key_hasher(Sha256)]
+struct HashedMessage {
+    field: i32,
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn test_hashed_key() {
+    let message = HashedMessage { field: 42 };
+    assert_eq!(message.cache_key_hasher(), Some(KeyHasher::Sha256));
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "HashedMessage::v0::7f7b819e4db943e95d1f31466f75dacb8ea1824394c364be1e55694380cc2727"
+            .to_string()
+    );
+}
+
+#[cfg(feature = "sha2")]
+#[derive(Cacheable)]
+#[cache_key_hasher(Sha256)]
+struct HashedFieldsMessage {
+    field: i32,
+    #[cache_key(skip)]
+    _trace_id: String,
+}
+
+#[cfg(feature = "sha2")]
+#[test]
+fn test_hashed_key_fields() {
+    let message = HashedFieldsMessage {
+        field: 42,
+        _trace_id: "trace".to_owned(),
+    };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "HashedFieldsMessage::v0::7f7b819e4db943e95d1f31466f75dacb8ea1824394c364be1e55694380cc2727"
+            .to_string()
+    );
+}
+
+#[test]
+fn test_key_is_not_hashed_by_default() {
+    let message = Message {
+        id: 0,
+        alias: "alias".to_string(),
+    };
+    assert_eq!(message.cache_key_hasher(), None);
+}
+
+#[derive(Serialize, Hash)]
+enum Filter {
+    Equal(String),
+    Range { from: i32, to: i32 },
+}
+
+#[derive(Cacheable, Serialize)]
+#[cache_key_encoder(JsonEncoder)]
+struct JsonMessage {
+    filters: std::collections::HashMap<String, Filter>,
+    page: Option<i32>,
+}
+
+#[test]
+fn test_json_encoder() {
+    let mut filters = std::collections::HashMap::new();
+    filters.insert("price".to_owned(), Filter::Range { from: 1, to: 10 });
+    filters.insert("color".to_owned(), Filter::Equal("red".to_owned()));
+    let message = JsonMessage {
+        filters,
+        page: None,
+    };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        r#"JsonMessage::v0::{"filters":{"color":{"Equal":"red"},"price":{"Range":{"from":1,"to":10}}},"page":null}"#
+            .to_string()
+    );
+}
+
+#[derive(Cacheable)]
+#[cache_key_encoder(JsonEncoder)]
+#[allow(dead_code)]
+struct JsonFieldsMessage {
+    #[cache_key(rename = "f")]
+    filter: Filter,
+    #[cache_key(skip)]
+    request_id: String,
+}
+
+#[test]
+fn test_json_encoder_fields() {
+    let message = JsonFieldsMessage {
+        filter: Filter::Equal("red".to_owned()),
+        request_id: "d1b2f7a3".to_owned(),
+    };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        r#"JsonFieldsMessage::v0::f={"Equal":"red"}"#.to_string()
+    );
+}
+
+#[derive(Cacheable, Hash)]
+#[cache_key_encoder(HashEncoder)]
+struct HashEncodedMessage {
+    filter: Filter,
+}
+
+#[test]
+fn test_hash_encoder() {
+    let first = HashEncodedMessage {
+        filter: Filter::Range { from: 1, to: 10 },
+    };
+    let second = HashEncodedMessage {
+        filter: Filter::Range { from: 1, to: 20 },
+    };
+    let key = first.cache_key().unwrap();
+    assert!(key.starts_with("HashEncodedMessage::v0::"));
+    assert_eq!(key, first.cache_key().unwrap());
+    assert_ne!(key, second.cache_key().unwrap());
+}
+
+#[derive(Cacheable)]
+#[cache_key_encoder(JsonEncoder)]
+enum JsonCommand<T> {
+    Find { filter: Filter },
+    Get(T),
+}
+
+#[test]
+fn test_json_encoder_enum() {
+    let command: JsonCommand<i32> = JsonCommand::Find {
+        filter: Filter::Range { from: 1, to: 2 },
+    };
+    assert_eq!(
+        command.cache_key().unwrap(),
+        r#"JsonCommand<i32>::Find::v0::filter={"Range":{"from":1,"to":2}}"#.to_string()
+    );
+    let command: JsonCommand<Vec<i32>> = JsonCommand::Get(vec![1, 2]);
+    assert_eq!(
+        command.cache_key().unwrap(),
+        "JsonCommand<Vec<i32>>::Get::v0::0=[1,2]".to_string()
+    );
+}
+
+struct UpperEncoder;
+
+impl KeyEncoder<String> for UpperEncoder {
+    fn encode(value: &String) -> Result<String, CacheError> {
+        Ok(value.to_uppercase())
+    }
+}
+
+#[derive(Cacheable)]
+#[cache_key_encoder(UpperEncoder)]
+struct CustomEncodedMessage {
+    #[cache_key(rename = "q")]
+    query: String,
+}
+
+#[test]
+fn test_custom_encoder() {
+    let message = CustomEncodedMessage {
+        query: "find".to_owned(),
+    };
+    assert_eq!(
+        message.cache_key().unwrap(),
+        "CustomEncodedMessage::v0::q=FIND".to_string()
+    );
+}
@@ -1,5 +1,6 @@
-use hitbox::{CachePolicy, CacheableResponse};
+use hitbox::prelude::*;
 use serde::Serialize;
+use serde_json::json;
 
 #[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
 struct Message {
@@ -34,3 +35,220 @@ fn test_custom_enum_message_into_policy() {
         CachePolicy::NonCacheable(_) => panic!(),
     };
 }
+
+#[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
+struct GenericMessage<T> {
+    items: Vec<T>,
+}
+
+#[test]
+fn test_generic_message_into_policy() {
+    let message = GenericMessage { items: vec![1, 2] };
+    let policy = message.clone().into_cache_policy();
+    match policy {
+        CachePolicy::Cacheable(value) => assert_eq!(value, message),
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    assert_eq!(GenericMessage::from_cached(message.clone()), message);
+}
+
+#[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
+enum HttpResponse {
+    #[cacheable]
+    Ok(String),
+    Unauthorized(i32),
+}
+
+#[test]
+fn test_cacheable_variant() {
+    let response = HttpResponse::Ok("body".to_owned());
+    match response.cache_policy() {
+        CachePolicy::Cacheable(value) => assert_eq!(serde_json::to_value(value).unwrap(), "body"),
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    match response.into_cache_policy() {
+        CachePolicy::Cacheable(value) => assert_eq!(value, "body".to_owned()),
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    assert_eq!(
+        HttpResponse::from_cached("body".to_owned()),
+        HttpResponse::Ok("body".to_owned())
+    );
+}
+
+#[test]
+fn test_non_cacheable_variant() {
+    let response = HttpResponse::Unauthorized(401);
+    assert!(matches!(
+        response.cache_policy(),
+        CachePolicy::NonCacheable(())
+    ));
+    match response.clone().into_cache_policy() {
+        CachePolicy::Cacheable(_) => panic!(),
+        CachePolicy::NonCacheable(value) => assert_eq!(value, response),
+    };
+}
+
+#[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
+enum SearchResponse {
+    Found {
+        items: Vec<i32>,
+    },
+    #[non_cacheable]
+    Empty,
+    #[non_cacheable]
+    Timeout,
+}
+
+#[test]
+fn test_non_cacheable_attribute() {
+    let response = SearchResponse::Found { items: vec![1] };
+    match response.into_cache_policy() {
+        CachePolicy::Cacheable(value) => assert_eq!(value, vec![1]),
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    assert!(matches!(
+        SearchResponse::Empty.cache_policy(),
+        CachePolicy::NonCacheable(())
+    ));
+    assert!(matches!(
+        SearchResponse::Timeout.cache_policy(),
+        CachePolicy::NonCacheable(())
+    ));
+    assert_eq!(
+        SearchResponse::from_cached(vec![2]),
+        SearchResponse::Found { items: vec![2] }
+    );
+}
+
+#[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
+enum DeleteResponse {
+    #[cacheable]
+    NotFound,
+    Deleted(i32),
+}
+
+#[test]
+fn test_unit_cacheable_variant() {
+    assert!(matches!(
+        DeleteResponse::NotFound.cache_policy(),
+        CachePolicy::Cacheable(_)
+    ));
+    assert!(matches!(
+        DeleteResponse::Deleted(1).into_cache_policy(),
+        CachePolicy::NonCacheable(DeleteResponse::Deleted(1))
+    ));
+    assert_eq!(DeleteResponse::from_cached(()), DeleteResponse::NotFound);
+}
+
+#[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
+enum GenericResponse<T> {
+    #[cacheable]
+    Ok {
+        value: T,
+    },
+    Error,
+}
+
+#[test]
+fn test_generic_cacheable_variant() {
+    let response = GenericResponse::Ok { value: 42 };
+    match response.into_cache_policy() {
+        CachePolicy::Cacheable(value) => assert_eq!(value, 42),
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    assert!(matches!(
+        GenericResponse::<i32>::Error.into_cache_policy(),
+        CachePolicy::NonCacheable(GenericResponse::Error)
+    ));
+    assert_eq!(
+        GenericResponse::from_cached(42),
+        GenericResponse::Ok { value: 42 }
+    );
+}
+
+#[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
+enum UserResponse {
+    Found(String),
+    NotFound,
+    #[non_cacheable]
+    Unavailable(i32),
+}
+
+#[test]
+fn test_several_cacheable_variants() {
+    let response = UserResponse::Found("user".to_owned());
+    match response.cache_policy() {
+        CachePolicy::Cacheable(value) => {
+            assert_eq!(
+                serde_json::to_value(value).unwrap(),
+                json!({ "Found": "user" })
+            )
+        }
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    let cached = match response.into_cache_policy() {
+        CachePolicy::Cacheable(value) => value,
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    assert_eq!(
+        serde_json::to_value(&cached).unwrap(),
+        json!({ "Found": "user" })
+    );
+    assert_eq!(
+        UserResponse::from_cached(cached),
+        UserResponse::Found("user".to_owned())
+    );
+    let cached: UserResponseCached<String> = serde_json::from_value(json!("NotFound")).unwrap();
+    assert_eq!(UserResponse::from_cached(cached), UserResponse::NotFound);
+}
+
+#[test]
+fn test_non_cacheable_variant_of_several() {
+    let response = UserResponse::Unavailable(503);
+    assert!(matches!(
+        response.cache_policy(),
+        CachePolicy::NonCacheable(())
+    ));
+    match response.clone().into_cache_policy() {
+        CachePolicy::Cacheable(_) => panic!(),
+        CachePolicy::NonCacheable(value) => assert_eq!(value, response),
+    };
+}
+
+#[derive(CacheableResponse, Serialize, Clone, Debug, Eq, PartialEq)]
+enum GenericListResponse<T> {
+    #[cacheable]
+    Page {
+        items: Vec<T>,
+        next: Option<u32>,
+    },
+    #[cacheable]
+    Empty,
+    Error(String),
+}
+
+#[test]
+fn test_generic_several_cacheable_variants() {
+    let response = GenericListResponse::Page {
+        items: vec![1, 2],
+        next: Some(2),
+    };
+    match response.cache_policy() {
+        CachePolicy::Cacheable(value) => assert_eq!(
+            serde_json::to_value(value).unwrap(),
+            json!({ "Page": { "items": [1, 2], "next": 2 } })
+        ),
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    match response.clone().into_cache_policy() {
+        CachePolicy::Cacheable(cached) => {
+            assert_eq!(GenericListResponse::from_cached(cached), response)
+        }
+        CachePolicy::NonCacheable(_) => panic!(),
+    };
+    assert!(matches!(
+        GenericListResponse::<i32>::Error("error".to_owned()).into_cache_policy(),
+        CachePolicy::NonCacheable(GenericListResponse::Error(_))
+    ));
+}
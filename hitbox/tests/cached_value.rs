@@ -0,0 +1,124 @@
+use hitbox::prelude::*;
+use hitbox::{CacheState, CachedRecord, CachedValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct UserV1 {
+    id: i32,
+    name: String,
+}
+
+#[derive(CacheableResponse, Serialize, Deserialize, Debug, PartialEq)]
+#[cache_schema_version(2)]
+struct UserV2 {
+    id: i32,
+    first_name: String,
+    last_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct MigratedUser {
+    id: i32,
+    full_name: String,
+}
+
+impl CacheableResponse for MigratedUser {
+    type Cached = Self;
+    fn cache_policy(&self) -> CachePolicy<CachedRef<'_>, ()> {
+        CachePolicy::Cacheable(CachedRef::new(self))
+    }
+    fn into_cache_policy(self) -> CachePolicy<Self::Cached, Self> {
+        CachePolicy::Cacheable(self)
+    }
+    fn from_cached(cached: Self::Cached) -> Self {
+        cached
+    }
+    fn cache_schema_version() -> u32 {
+        1
+    }
+    fn migrate_cached(schema_version: u32, data: serde_json::Value) -> Option<Self::Cached> {
+        match schema_version {
+            0 => serde_json::from_value::<UserV1>(data)
+                .ok()
+                .map(|user| MigratedUser {
+                    id: user.id,
+                    full_name: user.name,
+                }),
+            _ => None,
+        }
+    }
+}
+
+fn cached_v1() -> Vec<u8> {
+    serde_json::to_vec(&serde_json::json!({
+        "data": { "id": 42, "name": "Jane Doe" },
+        "expired": chrono::Utc::now() + chrono::Duration::seconds(60),
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_schema_version_is_stored() {
+    let value = CachedValue::new(
+        UserV2 {
+            id: 42,
+            first_name: "Jane".to_owned(),
+            last_name: "Doe".to_owned(),
+        },
+        chrono::Utc::now(),
+    );
+    let stored: serde_json::Value = serde_json::from_slice(&value.serialize().unwrap()).unwrap();
+    assert_eq!(stored["schema_version"], 2);
+    let state = CacheState::<UserV2>::from_bytes("key", Some(&value.serialize().unwrap())).unwrap();
+    assert!(matches!(state, CacheState::Stale(_)));
+}
+
+#[test]
+fn test_schema_version_mismatch_is_miss() {
+    let state = CacheState::<UserV2>::from_bytes("key", Some(&cached_v1())).unwrap();
+    assert!(matches!(state, CacheState::Miss));
+}
+
+#[test]
+fn test_shape_mismatch_is_miss() {
+    let bytes = cached_v1();
+    let state = CacheState::<Result<UserV2, ()>>::from_bytes("key", Some(&bytes)).unwrap();
+    assert!(matches!(state, CacheState::Miss));
+}
+
+#[test]
+fn test_migrate_cached() {
+    let state = CacheState::<MigratedUser>::from_bytes("key", Some(&cached_v1())).unwrap();
+    match state {
+        CacheState::Actual(value) => assert_eq!(
+            value.into_inner(),
+            MigratedUser {
+                id: 42,
+                full_name: "Jane Doe".to_owned()
+            }
+        ),
+        _ => panic!("Migrated value expected"),
+    }
+}
+
+#[test]
+fn test_cached_record() {
+    let record = CachedRecord::from_bytes("key", &cached_v1()).unwrap();
+    assert_eq!(record.schema_version, 0);
+    assert_eq!(record.data["name"], "Jane Doe");
+    assert!(!record.is_stale());
+    assert!(record.age().is_none());
+
+    let user = UserV2 {
+        id: 42,
+        first_name: "Jane".to_owned(),
+        last_name: "Doe".to_owned(),
+    };
+    let expired = chrono::Utc::now() - chrono::Duration::seconds(1);
+    let bytes = CachedValue::new(user, expired).serialize().unwrap();
+    let record = CachedRecord::from_bytes("key", &bytes).unwrap();
+    assert_eq!(record.schema_version, 2);
+    assert!(record.is_stale());
+
+    assert!(CachedRecord::from_bytes("key", b"broken").is_err());
+}
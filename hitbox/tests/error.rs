@@ -0,0 +1,43 @@
+use hitbox::dev::BackendError;
+use hitbox::{CacheError, CacheState, CachedValue};
+
+#[test]
+fn test_upstream_error_classification() {
+    let error = CacheError::from(actix::MailboxError::Timeout);
+    assert!(error.is_upstream_error());
+    assert!(error.is_upstream_timeout());
+    assert!(!error.is_cache_error());
+
+    let error = CacheError::from(actix::MailboxError::Closed);
+    assert!(error.is_upstream_error());
+    assert!(!error.is_upstream_timeout());
+}
+
+#[test]
+fn test_cache_error_classification() {
+    let error = CacheError::from(BackendError::InternalError(Box::new(std::fmt::Error)));
+    assert!(error.is_cache_error());
+    assert!(!error.is_upstream_error());
+    assert!(CacheError::CacheKeyGenerationError("key".to_owned()).is_cache_error());
+}
+
+#[test]
+fn test_deserialize_error_contains_key() {
+    let bytes = b"not a json".to_vec();
+    let error = CacheState::<i32>::from_bytes("Upstream::Message", Some(&bytes))
+        .err()
+        .unwrap();
+    match error {
+        CacheError::DeserializeError { key, .. } => assert_eq!(key, "Upstream::Message"),
+        error => panic!("Unexpected error {:?}", error),
+    }
+}
+
+#[test]
+fn test_serialize_non_cacheable() {
+    let value: CachedValue<Option<i32>> = CachedValue::new(None, chrono::Utc::now());
+    assert!(matches!(
+        value.serialize(),
+        Err(CacheError::NonCacheableError)
+    ));
+}
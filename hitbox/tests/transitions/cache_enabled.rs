@@ -50,3 +50,88 @@ async fn test_cache_enabled_cache_miss_upstream_error() {
     let finish = only_cache::transition(initial_state).await;
     assert!(finish.result().is_err());
 }
+
+#[actix::test]
+async fn test_cache_enabled_cache_miss_writes_cacheable() {
+    let settings = CacheSettings {
+        cache: Status::Enabled,
+        stale: Status::Disabled,
+        lock: Status::Disabled,
+    };
+    let adapter = MockAdapter::build()
+        .with_upstream_value(Some(42))
+        .with_cache_miss()
+        .finish();
+    let initial_state = Initial::new(settings, adapter.clone());
+    let finish = only_cache::transition(initial_state).await;
+    assert_eq!(finish.result().unwrap(), Some(42));
+    assert_eq!(adapter.cache_writes().len(), 1);
+}
+
+#[actix::test]
+async fn test_cache_enabled_cache_miss_skips_non_cacheable() {
+    let settings = CacheSettings {
+        cache: Status::Enabled,
+        stale: Status::Disabled,
+        lock: Status::Disabled,
+    };
+    let adapter: MockAdapter<Option<i32>> = MockAdapter::build()
+        .with_upstream_value(None)
+        .with_cache_miss()
+        .finish();
+    let initial_state = Initial::new(settings, adapter.clone());
+    let finish = only_cache::transition(initial_state).await;
+    assert_eq!(finish.result().unwrap(), None);
+    assert!(adapter.cache_writes().is_empty());
+}
+
+#[actix::test]
+async fn test_cache_enabled_cache_error_writes_cacheable() {
+    let settings = CacheSettings {
+        cache: Status::Enabled,
+        stale: Status::Disabled,
+        lock: Status::Disabled,
+    };
+    let adapter = MockAdapter::build()
+        .with_upstream_value(Some(42))
+        .with_cache_error()
+        .finish();
+    let initial_state = Initial::new(settings, adapter.clone());
+    let finish = only_cache::transition(initial_state).await;
+    assert_eq!(finish.result().unwrap(), Some(42));
+    assert_eq!(adapter.cache_writes().len(), 1);
+}
+
+#[actix::test]
+async fn test_cache_enabled_cache_error_skips_non_cacheable() {
+    let settings = CacheSettings {
+        cache: Status::Enabled,
+        stale: Status::Disabled,
+        lock: Status::Disabled,
+    };
+    let adapter: MockAdapter<Option<i32>> = MockAdapter::build()
+        .with_upstream_value(None)
+        .with_cache_error()
+        .finish();
+    let initial_state = Initial::new(settings, adapter.clone());
+    let finish = only_cache::transition(initial_state).await;
+    assert_eq!(finish.result().unwrap(), None);
+    assert!(adapter.cache_writes().is_empty());
+}
+
+#[actix::test]
+async fn test_cache_enabled_cache_error_upstream_error() {
+    let settings = CacheSettings {
+        cache: Status::Enabled,
+        stale: Status::Disabled,
+        lock: Status::Disabled,
+    };
+    let adapter: MockAdapter<i32> = MockAdapter::build()
+        .with_upstream_error()
+        .with_cache_error()
+        .finish();
+    let initial_state = Initial::new(settings, adapter.clone());
+    let finish = only_cache::transition(initial_state).await;
+    assert!(finish.result().is_err());
+    assert!(adapter.cache_writes().is_empty());
+}
@@ -1,3 +1,4 @@
 mod cache_disabled;
 mod cache_enabled;
+mod outcome;
 mod stale;
@@ -0,0 +1,115 @@
+use chrono::{Duration, Utc};
+use hitbox::dev::MockAdapter;
+use hitbox::settings::{CacheSettings, Status};
+use hitbox::states::initial::Initial;
+use hitbox::CacheStatus;
+
+fn settings(cache: Status, stale: Status) -> CacheSettings {
+    CacheSettings {
+        cache,
+        stale,
+        lock: Status::Disabled,
+    }
+}
+
+#[actix::test]
+async fn test_outcome_bypass() {
+    let adapter = MockAdapter::build().with_upstream_value(42).finish();
+    let initial_state = Initial::new(settings(Status::Disabled, Status::Disabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await.unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::Bypass);
+    assert!(outcome.expired.is_none());
+    assert!(outcome.age().is_none());
+}
+
+#[actix::test]
+async fn test_outcome_hit() {
+    let adapter = MockAdapter::build()
+        .with_upstream_value(42)
+        .with_cache_actual(41)
+        .finish();
+    let initial_state = Initial::new(settings(Status::Enabled, Status::Enabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await.unwrap();
+    assert_eq!(outcome.result, 41);
+    assert_eq!(outcome.status, CacheStatus::Hit);
+    assert!(outcome.status.is_cached());
+    assert!(outcome.expired.is_some());
+}
+
+#[actix::test]
+async fn test_outcome_miss() {
+    let adapter = MockAdapter::build()
+        .with_upstream_value(42)
+        .with_cache_miss()
+        .finish();
+    let initial_state = Initial::new(settings(Status::Enabled, Status::Enabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await.unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::Miss);
+    assert!(!outcome.status.is_cached());
+}
+
+#[actix::test]
+async fn test_outcome_cache_error() {
+    let adapter = MockAdapter::build()
+        .with_upstream_value(42)
+        .with_cache_error()
+        .finish();
+    let initial_state = Initial::new(settings(Status::Enabled, Status::Enabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await.unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::Error);
+}
+
+#[actix::test]
+async fn test_outcome_stale() {
+    let expired = Utc::now() - Duration::seconds(10);
+    let adapter = MockAdapter::build()
+        .with_upstream_value(42)
+        .with_cache_stale(41, expired)
+        .finish();
+    let initial_state = Initial::new(settings(Status::Enabled, Status::Disabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await.unwrap();
+    assert_eq!(outcome.result, 41);
+    assert_eq!(outcome.status, CacheStatus::Stale);
+    assert_eq!(outcome.expired, Some(expired));
+}
+
+#[actix::test]
+async fn test_outcome_expired() {
+    let adapter = MockAdapter::build()
+        .with_upstream_value(42)
+        .with_cache_stale(41, Utc::now())
+        .finish();
+    let initial_state = Initial::new(settings(Status::Enabled, Status::Enabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await.unwrap();
+    assert_eq!(outcome.result, 42);
+    assert_eq!(outcome.status, CacheStatus::Expired);
+    assert!(outcome.expired.is_none());
+}
+
+#[actix::test]
+async fn test_outcome_stale_if_error() {
+    let expired = Utc::now();
+    let adapter = MockAdapter::build()
+        .with_upstream_error()
+        .with_cache_stale(41, expired)
+        .finish();
+    let initial_state = Initial::new(settings(Status::Enabled, Status::Enabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await.unwrap();
+    assert_eq!(outcome.result, 41);
+    assert_eq!(outcome.status, CacheStatus::StaleIfError);
+    assert_eq!(outcome.expired, Some(expired));
+}
+
+#[actix::test]
+async fn test_outcome_upstream_error() {
+    let adapter = MockAdapter::<i32>::build()
+        .with_upstream_error()
+        .with_cache_miss()
+        .finish();
+    let initial_state = Initial::new(settings(Status::Enabled, Status::Enabled), adapter);
+    let outcome = initial_state.transitions_with_outcome().await;
+    assert!(outcome.unwrap_err().is_upstream_error());
+}